@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_ui_widgets::theming::*;
+
+/// Number of themed nodes per row and per column.
+const GRID_SIZE: usize = 70;
+/// Number of frames updated before measuring.
+const WARMUP_FRAMES: u32 = 10;
+/// Number of frames measured for each scenario.
+const FRAMES: u32 = 200;
+
+/// Measures the frame time of the theming systems in a headless app with thousands of themed nodes.
+///
+/// Run with `cargo bench --bench theming`. The grid uses multiple classes per key, `:hover` and
+/// descendant selectors, so it can only be compared with revisions that support them.
+fn main() {
+    println!(
+        "{} themed nodes, average of {} frames",
+        GRID_SIZE * GRID_SIZE + GRID_SIZE + 1,
+        FRAMES
+    );
+    bench("idle", |_, _| {});
+    bench("hover one node", |world, frame| {
+        let interaction = if frame % 2 == 0 {
+            Interaction::Hovered
+        } else {
+            Interaction::None
+        };
+        let cell = world.resource::<Cells>().0[0];
+        *world.get_mut::<Interaction>(cell).unwrap() = interaction;
+    });
+    bench("change a property of every node", |world, frame| {
+        let lightness = if frame % 2 == 0 { 0.15 } else { 0.25 };
+        world.resource_mut::<ThemeManager>().set_property(
+            "cell",
+            ColorProperty(Color::rgb(lightness, lightness, lightness)),
+        );
+    });
+}
+
+/// The themed buttons of the grid.
+struct Cells(Vec<Entity>);
+
+fn bench(name: &str, mut change: impl FnMut(&mut World, u32)) {
+    let mut app = setup();
+    for frame in 0..WARMUP_FRAMES {
+        change(&mut app.world, frame);
+        app.update();
+    }
+    let mut elapsed = Duration::ZERO;
+    for frame in 0..FRAMES {
+        change(&mut app.world, frame);
+        let start = Instant::now();
+        app.update();
+        elapsed += start.elapsed();
+    }
+    println!(
+        "{:>36}: {:>8.1} µs/frame",
+        name,
+        elapsed.as_secs_f64() * 1e6 / FRAMES as f64
+    );
+}
+
+fn setup() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(ThemingPlugin);
+
+    app.world
+        .resource_mut::<ThemeManager>()
+        .set_property("root", FlexDirectionProperty(FlexDirection::ColumnReverse))
+        .set_property("row", FlexGrowProperty(1.0))
+        .set_property("cell", FlexGrowProperty(1.0))
+        .set_property("cell", MarginProperty(UiRect::all(Val::Px(1.0))))
+        .set_property("cell", ColorProperty(Color::rgb(0.15, 0.15, 0.15)))
+        .set_property("cell:hover", ColorProperty(Color::rgb(0.35, 0.35, 0.35)))
+        .set_property("row.odd cell", ColorProperty(Color::rgb(0.2, 0.2, 0.25)));
+
+    let world = &mut app.world;
+    let mut cells = Vec::with_capacity(GRID_SIZE * GRID_SIZE);
+    let mut rows = Vec::with_capacity(GRID_SIZE);
+    for row in 0..GRID_SIZE {
+        let key = if row % 2 == 1 { "row odd" } else { "row" };
+        let row_cells: Vec<_> = (0..GRID_SIZE)
+            .map(|_| {
+                world
+                    .spawn()
+                    .insert_bundle(ButtonBundle::default())
+                    .insert(ThemeKey::from("cell"))
+                    .id()
+            })
+            .collect();
+        let row = world
+            .spawn()
+            .insert_bundle(NodeBundle::default())
+            .insert(ThemeKey::from(key))
+            .push_children(&row_cells)
+            .id();
+        cells.extend(row_cells);
+        rows.push(row);
+    }
+    world
+        .spawn()
+        .insert_bundle(NodeBundle::default())
+        .insert(ThemeKey::from("root"))
+        .push_children(&rows);
+    world.insert_resource(Cells(cells));
+    app
+}
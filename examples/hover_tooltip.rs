@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{widgets::tooltip::*, AllWidgetsPlugins};
+
+/// A row of buttons displaying a rich text tooltip when hovered.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 20.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
+    let buttons: Vec<Entity> = ["New", "Open", "Save", "Close"]
+        .into_iter()
+        .map(|label| {
+            commands
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(80.0), Val::Px(40.0)),
+                        margin: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    color: Color::rgb(0.25, 0.25, 0.25).into(),
+                    ..default()
+                })
+                .insert(HasTooltip::Text(Text::from_sections([
+                    TextSection::new(format!("{} file ", label), text_style.clone()),
+                    TextSection::new(
+                        format!("(Ctrl+{})", &label[..1]),
+                        TextStyle {
+                            color: Color::rgb(0.6, 0.6, 0.6),
+                            ..text_style.clone()
+                        },
+                    ),
+                ])))
+                .id()
+        })
+        .collect();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .push_children(&buttons);
+}
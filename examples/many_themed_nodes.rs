@@ -0,0 +1,68 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_ui_widgets::{theming::*, AllWidgetsPlugins};
+
+/// Number of themed nodes per row and per column.
+const GRID_SIZE: usize = 70;
+
+/// Stress test for the theming systems, with thousands of themed nodes.
+///
+/// Frame times are logged every second. Hovering nodes only updates the hovered node, while
+/// pressing space changes a property every frame, which updates every node.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_startup_system(setup)
+        .add_system(pulse)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    theme
+        .set_property("root", FlexDirectionProperty(FlexDirection::ColumnReverse))
+        .set_property(
+            "root",
+            SizeProperty(Size::new(Val::Percent(100.0), Val::Percent(100.0))),
+        )
+        .set_property("row", FlexGrowProperty(1.0))
+        .set_property("cell", FlexGrowProperty(1.0))
+        .set_property("cell", MarginProperty(UiRect::all(Val::Px(1.0))))
+        .set_property("cell", ColorProperty(Color::rgb(0.15, 0.15, 0.15)))
+        .set_property("cell:hover", ColorProperty(Color::rgb(0.35, 0.35, 0.35)))
+        .set_property("row.odd cell", ColorProperty(Color::rgb(0.2, 0.2, 0.25)));
+
+    commands
+        .spawn_bundle(NodeBundle::default())
+        .insert(ThemeKey::from("root"))
+        .with_children(|root| {
+            for row in 0..GRID_SIZE {
+                let key = if row % 2 == 1 { "row odd" } else { "row" };
+                root.spawn_bundle(NodeBundle::default())
+                    .insert(ThemeKey::from(key))
+                    .with_children(|row| {
+                        for _ in 0..GRID_SIZE {
+                            row.spawn_bundle(ButtonBundle::default())
+                                .insert(ThemeKey::from("cell"));
+                        }
+                    });
+            }
+        });
+}
+
+/// Changes the color of every cell while space is pressed.
+fn pulse(keys: Res<Input<KeyCode>>, time: Res<Time>, mut theme: ResMut<ThemeManager>) {
+    if keys.pressed(KeyCode::Space) {
+        let lightness = 0.15 + 0.1 * time.seconds_since_startup().sin().abs() as f32;
+        theme.set_property(
+            "cell",
+            ColorProperty(Color::rgb(lightness, lightness, lightness)),
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{widgets::scroll_view::*, AllWidgetsPlugins};
+
+/// Scrolls a long list of rows with the mouse wheel, the touchpad or the scrollbars.
+/// Hold shift to scroll horizontally with a mouse wheel, and press Home or End to scroll to the
+/// first or last row.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .add_system(scroll_to_row)
+        .run();
+}
+
+struct Rows(Vec<Entity>);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 20.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let rows: Vec<_> = (1..=50)
+        .map(|i| {
+            let text = commands
+                .spawn_bundle(TextBundle::from_section(
+                    format!("Row {} of a list that is wider than the scroll view", i),
+                    text_style.clone(),
+                ))
+                .id();
+            commands
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    color: if i % 2 == 0 {
+                        Color::rgb(0.15, 0.15, 0.15).into()
+                    } else {
+                        Color::rgb(0.1, 0.1, 0.1).into()
+                    },
+                    ..default()
+                })
+                .add_child(text)
+                .id()
+        })
+        .collect();
+
+    let mut builder = ScrollViewWidgetBuilder::new();
+    builder
+        .root_bundle(|bundle| NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(300.0), Val::Px(400.0)),
+                margin: UiRect::all(Val::Auto),
+                ..bundle.style
+            },
+            ..bundle
+        })
+        .with_scrollbar(ScrollAxis::Horizontal);
+    for row in &rows {
+        builder.with_content(*row);
+    }
+    builder.spawn(&mut commands);
+
+    commands.insert_resource(Rows(rows));
+}
+
+fn scroll_to_row(
+    keys: Res<Input<KeyCode>>,
+    rows: Res<Rows>,
+    mut scroll_to: EventWriter<ScrollToEntity>,
+) {
+    let row = if keys.just_pressed(KeyCode::Home) {
+        rows.0.first()
+    } else if keys.just_pressed(KeyCode::End) {
+        rows.0.last()
+    } else {
+        None
+    };
+    if let Some(row) = row {
+        scroll_to.send(ScrollToEntity(*row));
+    }
+}
@@ -28,8 +28,22 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             color: Color::NONE.into(),
             ..bundle
         })
+        .with_value_label(TextStyle {
+            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+            font_size: 20.0,
+            color: Color::rgb(0.9, 0.9, 0.9),
+        })
         .spawn(&mut commands);
 
+    commands
+        .entity(slider.root)
+        .insert(SliderValueFormatter::labels([
+            (0, "Off"),
+            (10, "Low"),
+            (40, "Medium"),
+            (70, "High"),
+        ]));
+
     commands.entity(slider.root).insert(SliderTooltip {
         text_style: TextStyle {
             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
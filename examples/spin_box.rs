@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{widgets::spin_box::*, AllWidgetsPlugins};
+
+/// Hold a button to step the value repeatedly, or drag a label left and right to scrub it.
+/// Typed values are clamped and rounded to a step when enter is pressed or the field loses focus.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .add_system(log_values)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let root = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .id();
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        ..default()
+    };
+    let width = SpinBoxWidgetBuilder::new()
+        .text_style(text_style.clone())
+        .label("Width")
+        .min(0)
+        .max(1920)
+        .value(800)
+        .spawn(&mut commands)
+        .root;
+    let angle = SpinBoxWidgetBuilder::new()
+        .text_style(text_style)
+        .label("Angle")
+        .min(-180)
+        .max(180)
+        .step(15)
+        .spawn(&mut commands)
+        .root;
+    commands.entity(root).push_children(&[width, angle]);
+}
+
+fn log_values(spin_box_q: Query<(Entity, &SpinBox), Changed<SpinBox>>) {
+    for (entity, spin_box) in spin_box_q.iter() {
+        info!("{:?} changed to {}", entity, spin_box.value);
+    }
+}
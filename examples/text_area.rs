@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{
+    widgets::{text_area::*, text_input::TextInputChanged},
+    AllWidgetsPlugins,
+};
+
+/// Click the text area to focus it and type. Enter adds a line, and control with enter submits.
+/// Control with Z undoes the last edit, and control with Y or shift Z redoes it.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .add_system(log_changes)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let root = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .id();
+
+    let text_area = TextAreaWidgetBuilder::new()
+        .text_style(TextStyle {
+            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+            ..default()
+        })
+        .placeholder("Write some notes...")
+        .value("The text wraps at the width of the area.\nThe area scrolls to follow the caret.")
+        .scroll_view(|scroll_view| {
+            scroll_view.root_bundle(|bundle| NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Px(400.0), Val::Px(200.0)),
+                    ..bundle.style
+                },
+                ..bundle
+            });
+        })
+        .spawn(&mut commands)
+        .scroll_view
+        .root;
+    commands.entity(root).add_child(text_area);
+}
+
+fn log_changes(mut changed: EventReader<TextInputChanged>) {
+    for event in changed.iter() {
+        info!("{:?} changed to {:?}", event.entity, event.value);
+    }
+}
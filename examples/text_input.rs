@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{theming::*, widgets::text_input::*, AllWidgetsPlugins};
+
+/// Click a text input to focus it and type. Enter submits the value, and escape removes the focus.
+/// Control with A, C, X and V selects, copies, cuts and pastes text between the inputs.
+///
+/// The inputs are styled by the built-in theme, which colours their border when focused.
+fn main() {
+    App::new()
+        .insert_resource(ThemeManager::with_builtin(ThemeVariant::Dark))
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .add_system(log_events)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let root = commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .id();
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        ..default()
+    };
+    let name = TextInputWidgetBuilder::new()
+        .text_style(text_style.clone())
+        .placeholder("Name")
+        .max_length(20)
+        .root_bundle(input_bundle)
+        .spawn(&mut commands)
+        .root;
+    let password = TextInputWidgetBuilder::new()
+        .text_style(text_style.clone())
+        .placeholder("Password")
+        .mask('*')
+        .root_bundle(input_bundle)
+        .spawn(&mut commands)
+        .root;
+    let age = TextInputWidgetBuilder::new()
+        .text_style(text_style)
+        .placeholder("Age")
+        .max_length(3)
+        .filter(CharacterFilter::Digits)
+        .root_bundle(input_bundle)
+        .spawn(&mut commands)
+        .root;
+    commands.entity(root).push_children(&[name, password, age]);
+}
+
+fn input_bundle(bundle: NodeBundle) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(250.0), Val::Auto),
+            margin: UiRect::all(Val::Px(5.0)),
+            ..bundle.style
+        },
+        ..bundle
+    }
+}
+
+fn log_events(
+    mut changed: EventReader<TextInputChanged>,
+    mut submitted: EventReader<TextInputSubmitted>,
+) {
+    for event in changed.iter() {
+        info!("{:?} changed to {:?}", event.entity, event.value);
+    }
+    for event in submitted.iter() {
+        info!("{:?} submitted {:?}", event.entity, event.value);
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::{asset::AssetServerSettings, prelude::*};
+use bevy_ui_widgets::{theming::*, AllWidgetsPlugins};
+
+/// Loads the theme from `assets/themes/example.theme.ron`.
+/// Edit the file while the example is running to see the changes applied immediately, and
+/// mistakes such as unknown classes or missing variables reported as warnings.
+/// Press S to save the theme, including properties set in code, to
+/// `assets/themes/exported.theme.ron`.
+fn main() {
+    App::new()
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_plugin(ThemeDiagnosticsPlugin)
+        .add_startup_system(setup)
+        .add_system(save_theme)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    theme.set_theme_source(
+        ThemeManager::DEFAULT_THEME,
+        asset_server.load("themes/example.theme.ron"),
+    );
+
+    let title = commands
+        .spawn_bundle(TextBundle::from_section("Theme file", default()))
+        .insert(ThemeKey::from("title"))
+        .id();
+
+    let text = commands
+        .spawn_bundle(TextBundle::from_section(
+            "Edit example.theme.ron to change this panel.",
+            default(),
+        ))
+        .insert(ThemeKey::from("text"))
+        .id();
+
+    let panel = commands
+        .spawn_bundle(NodeBundle::default())
+        .insert(ThemeKey::from("panel"))
+        .push_children(&[title, text])
+        .id();
+
+    commands
+        .spawn_bundle(NodeBundle::default())
+        .insert(ThemeKey::from("root"))
+        .add_child(panel);
+}
+
+fn save_theme(keys: Res<Input<KeyCode>>, theme: Res<ThemeManager>) {
+    if keys.just_pressed(KeyCode::S) {
+        let path = "assets/themes/exported.theme.ron";
+        match theme.save_theme(theme.active_theme_name(), path) {
+            Ok(()) => info!("theme saved to {}", path),
+            Err(error) => error!("{}", error),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use bevy_ui_widgets::{theming::*, widgets::theme_inspector::*, AllWidgetsPlugins};
+
+/// Inspects and edits the theme of a few buttons with the theme inspector.
+/// Hover an entity to see its properties, and click it to edit them with the inspector's fields.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AllWidgetsPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    theme
+        .set_property("root", FlexDirectionProperty(FlexDirection::ColumnReverse))
+        .set_property("root", JustifyContentProperty(JustifyContent::Center))
+        .set_property("root", AlignItemsProperty(AlignItems::Center))
+        .set_property(
+            "root",
+            SizeProperty(Size::new(Val::Percent(100.0), Val::Percent(100.0))),
+        )
+        .set_property("button", JustifyContentProperty(JustifyContent::Center))
+        .set_property("button", MarginProperty(UiRect::all(Val::Px(5.0))))
+        .set_property(
+            "button",
+            SizeProperty(Size::new(Val::Px(150.0), Val::Px(50.0))),
+        )
+        .set_property("button", ColorProperty(Color::rgb(0.15, 0.15, 0.15)))
+        .set_property("button:hover", ColorProperty(Color::rgb(0.25, 0.25, 0.25)))
+        .set_property("button.primary", ColorProperty(Color::rgb(0.1, 0.3, 0.6)))
+        .set_property(
+            "text",
+            TextFontPathProperty("fonts/FiraSans-Bold.ttf".into()),
+        )
+        .set_property("text", TextFontSizeProperty(20.0))
+        .set_property("text", TextColorProperty(Color::rgb(0.9, 0.9, 0.9)));
+
+    let root = commands
+        .spawn_bundle(NodeBundle {
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(ThemeKey::from("root"))
+        .id();
+    for (key, label) in [("button primary", "Primary"), ("button", "Secondary")] {
+        let text = commands
+            .spawn_bundle(TextBundle::from_section(label, default()))
+            .insert(ThemeKey::from("text"))
+            .id();
+        let button = commands
+            .spawn_bundle(ButtonBundle::default())
+            .insert(ThemeKey::from(key))
+            .add_child(text)
+            .id();
+        commands.entity(root).add_child(button);
+    }
+
+    ThemeInspectorWidgetBuilder::new()
+        .text_style(TextStyle {
+            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+            font_size: 14.0,
+            color: Color::rgb(0.9, 0.9, 0.9),
+        })
+        .spawn(&mut commands);
+}
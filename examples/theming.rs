@@ -1,73 +1,118 @@
 use bevy::prelude::*;
-use bevy_ui_widgets::{components::toggle::Toggle, theming::*, *};
+use bevy_ui_widgets::{components::toggle::Toggle, define_theme_property, theming::*, *};
+
+// A custom property, applied to the `Visibility` component by the binding registered below.
+define_theme_property!(VisibleProperty, bool, "visible");
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(AllWidgetsPlugins)
+        .register_theme_property::<VisibleProperty>()
+        .bind_theme_property::<VisibleProperty, Visibility>(|property, mut visibility| {
+            if visibility.is_visible != property.0 {
+                visibility.is_visible = property.0;
+            }
+        })
         .add_startup_system(setup)
         .add_system(toggle_theme)
         .run();
 }
 
-fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>) {
-    commands.spawn_bundle(Camera2dBundle::default());
-
-    // static theme variables
+/// Properties shared by the light and dark themes.
+fn shared_theme() -> Theme {
+    let mut theme = Theme::new();
     theme
         .set_property("root", FlexDirectionProperty(FlexDirection::ColumnReverse))
         .set_property("root", JustifyContentProperty(JustifyContent::Center))
         .set_property("root", AlignItemsProperty(AlignItems::Center))
         .set_property("root", SizeProperty(Size::new(Val::Percent(100.0), Val::Percent(100.0))))
-        .set_property("root", ColorProperty(Color::WHITE))
         .set_property("button", JustifyContentProperty(JustifyContent::Center))
         .set_property("button", AlignItemsProperty(AlignItems::Center))
         .set_property("button", PaddingProperty(UiRect::all(Val::Px(10.0))))
         .set_property("button", ColorProperty(Color::rgb(0.15, 0.15, 0.15)))
         .set_property("text", TextFontPathProperty("fonts/FiraSans-Bold.ttf".into()))
         .set_property("text", TextFontSizeProperty(20.0))
-        .set_property("text", TextColorProperty(Color::RED));
+        .set_property("root", fade(0.3))
+        .set_property("text", fade(0.3));
+    theme
+}
+
+/// Animates color changes when switching themes.
+fn fade(duration: f32) -> TransitionProperty {
+    let transition = Transition {
+        duration,
+        easing: Easing::EaseInOut,
+        ..default()
+    };
+    TransitionProperty(
+        [
+            (ColorProperty::PROPERTY_NAME.to_string(), transition.clone()),
+            (TextColorProperty::PROPERTY_NAME.to_string(), transition),
+        ]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    let mut light = shared_theme();
+    light
+        .set_property("root", ColorProperty(Color::GRAY))
+        .set_property("text", TextColorProperty(Color::RED))
+        .set_property("text::action", TextColorProperty(Color::WHITE))
+        .set_property("dark-only", VisibleProperty(false));
+
+    let mut dark = shared_theme();
+    dark.set_property("root", ColorProperty(Color::BLACK))
+        .set_property("text", TextColorProperty(Color::GREEN))
+        .set_property("text::action", TextColorProperty(Color::YELLOW))
+        .set_property("dark-only", VisibleProperty(true));
+
+    theme
+        .insert_theme("light", light)
+        .insert_theme("dark", dark)
+        .set_active_theme("light");
 
     let root = commands
         .spawn_bundle(NodeBundle::default())
-        .insert(ThemeKey("root".into()))
+        .insert(ThemeKey::from("root"))
         .id();
 
+    // Sections can be themed separately, by index (`"text::1"`) or by tag (`"text::action"`).
     let text = commands
-        .spawn_bundle(TextBundle::from_section(
-            "Click here to toggle theme",
-            default(),
-        ))
-        .insert(ThemeKey("text".into()))
+        .spawn_bundle(TextBundle::from_sections([
+            TextSection::new("Click here to ", default()),
+            TextSection::new("toggle theme", default()),
+        ]))
+        .insert(ThemeKey::from("text"))
+        .insert(TextSectionTags::default().with_tag(1, "action"))
+        .id();
+
+    let moon = commands
+        .spawn_bundle(TextBundle::from_section("(dark)", default()))
+        .insert(ThemeKey::from("text dark-only"))
         .id();
 
     let button = commands
         .spawn_bundle(ButtonBundle::default())
-        .insert(ThemeKey("button".into()))
+        .insert(ThemeKey::from("button"))
         .insert(Toggle::default())
         .add_child(text)
+        .add_child(moon)
         .id();
 
     commands.entity(root).add_child(button);
 }
 
-/// Changes some theme properties when the button is toggled.
-/// This basically shows how a light/dark mode could be achieved.
-///
-/// TODO: try to implement this via swapping pre-filled themes and using insert.
+/// Switches between the pre-filled "light" and "dark" themes when the button is toggled.
 fn toggle_theme(mut query: Query<&Toggle, Changed<Toggle>>, mut theme: ResMut<ThemeManager>) {
     for toggle in query.iter_mut() {
         match toggle {
-            Toggle::Off => {
-                theme
-                    .set_property("root", ColorProperty(Color::BLACK))
-                    .set_property("text", TextColorProperty(Color::GREEN));
-            }
-            Toggle::On => {
-                theme
-                    .set_property("root", ColorProperty(Color::GRAY))
-                    .set_property("text", TextColorProperty(Color::RED));
-            }
-        }
+            Toggle::Off => theme.set_active_theme("dark"),
+            Toggle::On => theme.set_active_theme("light"),
+        };
     }
 }
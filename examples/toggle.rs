@@ -1,25 +1,33 @@
 use bevy::prelude::*;
-use bevy_ui_widgets::{
-    components::{
-        toggle::Toggle,
-    },
-    *,
-};
+use bevy_ui_widgets::{components::toggle::Toggle, theming::*, *};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(AllWidgetsPlugins)
         .add_startup_system(setup)
-        .add_system(toggle_color)
         .run();
 }
 
-const TOGGLE_OFF_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
-const TOGGLE_ON_COLOR: Color = Color::rgb(0.35, 0.75, 0.35);
-
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, mut theme: ResMut<ThemeManager>) {
     commands.spawn_bundle(Camera2dBundle::default());
+
+    theme
+        .set_variable("off", ThemeVariable::Color(Color::rgb(0.15, 0.15, 0.15)))
+        .set_variable("on", ThemeVariable::Color(Color::rgb(0.35, 0.75, 0.35)));
+
+    // The most specific selector wins, so states can be combined to style each case.
+    theme
+        .set_property_ref::<ColorProperty>("toggle", VariableRef::new("off"))
+        .set_property_ref::<ColorProperty>("toggle:hover", VariableRef::new("off").lighten(0.04))
+        .set_property_ref::<ColorProperty>("toggle:pressed", VariableRef::new("off").darken(0.04))
+        .set_property_ref::<ColorProperty>("toggle:on", VariableRef::new("on"))
+        .set_property_ref::<ColorProperty>("toggle:on:hover", VariableRef::new("on").lighten(0.04))
+        .set_property_ref::<ColorProperty>(
+            "toggle:on:pressed",
+            VariableRef::new("on").darken(0.04),
+        );
+
     commands
         .spawn_bundle(ButtonBundle {
             style: Style {
@@ -32,47 +40,8 @@ fn setup(mut commands: Commands) {
                 align_items: AlignItems::Center,
                 ..default()
             },
-            color: TOGGLE_OFF_COLOR.into(),
             ..default()
         })
-        .insert(Toggle::default());
-}
-
-fn toggle_color(
-    mut query: Query<
-        (&Toggle, &Interaction, &mut UiColor),
-        Or<(Changed<Toggle>, Changed<Interaction>)>,
-    >,
-) {
-    for (toggle, interaction, mut color) in query.iter_mut() {
-        let lighten_value = match interaction {
-            Interaction::Clicked => -0.04,
-            Interaction::Hovered => 0.04,
-            Interaction::None => 0.0,
-        };
-
-        color.0 = match toggle {
-            Toggle::Off => lighten(TOGGLE_OFF_COLOR, lighten_value).into(),
-            Toggle::On => lighten(TOGGLE_ON_COLOR, lighten_value).into(),
-        }
-    }
-}
-
-fn lighten(color: Color, value: f32) -> Color {
-    if let Color::Hsla {
-        hue,
-        saturation,
-        lightness,
-        alpha,
-    } = color.as_hsla()
-    {
-        Color::Hsla {
-            hue,
-            saturation,
-            lightness: lightness + value,
-            alpha,
-        }
-    } else {
-        default()
-    }
+        .insert(Toggle::default())
+        .insert(ThemeKey::from("toggle"));
 }
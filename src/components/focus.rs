@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_input::prelude::*;
+use bevy_math::Vec2;
+use bevy_transform::components::GlobalTransform;
+use bevy_ui::*;
+
+use super::state::{Disabled, Focused};
+use crate::theming::ThemingSystem;
+
+pub struct FocusComponentsPlugin;
+
+/// Plugin that enables the systems for focus-related components
+impl Plugin for FocusComponentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            focus.after(UiSystem::Focus).before(ThemingSystem::Match),
+        )
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            focus_navigation
+                .after(UiSystem::Focus)
+                .before(ThemingSystem::Match),
+        );
+    }
+}
+
+/// Added to a UI node that can get keyboard focus.
+///
+/// If [`Interaction`] is also present, clicking the node adds the [`Focused`] component to it,
+/// and clicking anywhere else removes it. Nodes that are [`Disabled`] can't be focused.
+///
+/// Tab moves the focus to the next focusable node, and shift and tab to the previous one. Nodes are
+/// visited as they are laid out, from top to bottom and then from left to right.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct Focusable;
+
+fn focus(
+    mut commands: Commands,
+    mouse: Res<Input<MouseButton>>,
+    focusable_q: Query<(Entity, &Interaction), (With<Focusable>, Without<Disabled>)>,
+    focused_q: Query<Entity, With<Focused>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let clicked = focusable_q
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Clicked)
+        .map(|(entity, _)| entity);
+    for entity in focused_q.iter() {
+        if Some(entity) != clicked {
+            commands.entity(entity).remove::<Focused>();
+        }
+    }
+    if let Some(entity) = clicked.filter(|entity| !focused_q.contains(*entity)) {
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+/// Moves the focus with tab and shift and tab.
+///
+/// Nodes that aren't laid out, e.g. because they are hidden with `Display::None`, are skipped.
+fn focus_navigation(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    focusable_q: Query<(Entity, &Node, &GlobalTransform), (With<Focusable>, Without<Disabled>)>,
+    focused_q: Query<Entity, With<Focused>>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let backwards = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let nodes = focusable_q
+        .iter()
+        .filter(|(_, node, _)| node.size != Vec2::ZERO)
+        .map(|(entity, node, transform)| {
+            let top_left = transform.translation().truncate() + node.size * Vec2::new(-0.5, 0.5);
+            (entity, top_left)
+        })
+        .collect();
+    let focused = focused_q.iter().next();
+    let next = match next_focus(nodes, focused, backwards) {
+        Some(next) => next,
+        None => return,
+    };
+    for entity in focused_q.iter().filter(|entity| *entity != next) {
+        commands.entity(entity).remove::<Focused>();
+    }
+    commands.entity(next).insert(Focused);
+}
+
+/// Returns the node focused after `focused` when moving forwards or backwards, given the top left
+/// corner of each node.
+///
+/// Nodes are ordered from top to bottom and then from left to right, and the order wraps around.
+/// Without a focused node, the first or the last node is focused.
+fn next_focus(
+    mut nodes: Vec<(Entity, Vec2)>,
+    focused: Option<Entity>,
+    backwards: bool,
+) -> Option<Entity> {
+    // UI coordinates grow upwards, so the top nodes have the largest `y`.
+    nodes.sort_by(|(_, a), (_, b)| {
+        b.y.partial_cmp(&a.y)
+            .unwrap_or(Ordering::Equal)
+            .then(a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal))
+    });
+    let len = nodes.len();
+    let focused =
+        focused.and_then(|focused| nodes.iter().position(|(entity, _)| *entity == focused));
+    let index = match focused {
+        Some(index) if backwards => (index + len - 1) % len,
+        Some(index) => (index + 1) % len,
+        None if backwards => len.checked_sub(1)?,
+        None => 0,
+    };
+    nodes.get(index).map(|(entity, _)| *entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<(Entity, Vec2)> {
+        // Two nodes on the top row, in reverse order, and one below them.
+        vec![
+            (Entity::from_raw(1), Vec2::new(100.0, 500.0)),
+            (Entity::from_raw(2), Vec2::new(0.0, 300.0)),
+            (Entity::from_raw(0), Vec2::new(0.0, 500.0)),
+        ]
+    }
+
+    #[test]
+    fn focus_moves_from_top_to_bottom_and_left_to_right() {
+        let next = |focused| next_focus(nodes(), Some(Entity::from_raw(focused)), false);
+        assert_eq!(next(0), Some(Entity::from_raw(1)));
+        assert_eq!(next(1), Some(Entity::from_raw(2)));
+        assert_eq!(next(2), Some(Entity::from_raw(0)));
+    }
+
+    #[test]
+    fn focus_moves_backwards() {
+        let previous = |focused| next_focus(nodes(), Some(Entity::from_raw(focused)), true);
+        assert_eq!(previous(0), Some(Entity::from_raw(2)));
+        assert_eq!(previous(2), Some(Entity::from_raw(1)));
+        assert_eq!(previous(1), Some(Entity::from_raw(0)));
+    }
+
+    #[test]
+    fn first_or_last_node_is_focused_without_focus() {
+        assert_eq!(next_focus(nodes(), None, false), Some(Entity::from_raw(0)));
+        assert_eq!(next_focus(nodes(), None, true), Some(Entity::from_raw(2)));
+        assert_eq!(next_focus(Vec::new(), None, false), None);
+        assert_eq!(next_focus(Vec::new(), None, true), None);
+    }
+}
@@ -1,2 +1,4 @@
+pub mod focus;
 pub mod toggle;
 pub mod grab;
+pub mod state;
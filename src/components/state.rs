@@ -0,0 +1,13 @@
+use bevy_ecs::prelude::*;
+
+/// Added to a UI node that currently has keyboard focus.
+///
+/// Themes can target focused nodes with the `:focused` state, e.g. `"button:focused"`.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct Focused;
+
+/// Added to a UI node that can't currently be interacted with.
+///
+/// Themes can target disabled nodes with the `:disabled` state, e.g. `"button:disabled"`.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct Disabled;
@@ -15,9 +15,15 @@ impl PluginGroup for AllWidgetsPlugins {
     fn build(&mut self, group: &mut PluginGroupBuilder) {
         group.add(components::grab::GrabComponentsPlugin);
         group.add(components::toggle::ToggleComponentsPlugin);
+        group.add(components::focus::FocusComponentsPlugin);
         group.add(theming::ThemingPlugin);
         group.add(widgets::frame::FramePlugin);
         group.add(widgets::tooltip::TooltipPlugin);
         group.add(widgets::slider::SliderPlugin);
+        group.add(widgets::scroll_view::ScrollViewPlugin);
+        group.add(widgets::text_input::TextInputPlugin);
+        group.add(widgets::text_area::TextAreaPlugin);
+        group.add(widgets::spin_box::SpinBoxPlugin);
+        group.add(widgets::theme_inspector::ThemeInspectorPlugin);
     }
 }
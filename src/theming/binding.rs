@@ -0,0 +1,71 @@
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use serde::de::DeserializeOwned;
+
+use super::{
+    ThemeKey, ThemeManager, ThemeMarkers, ThemeNodes, ThemeProperty, ThemePropertyName,
+    ThemePropertyRegistry, ThemingSystem,
+};
+
+/// Components that can be themed by a property binding.
+type BoundQuery<'w, 's, C> =
+    Query<'w, 's, (Entity, &'static mut C), Or<(With<ThemeKey>, With<ThemeMarkers>)>>;
+
+pub trait ThemePropertyAppExt {
+    /// Allows the `T` property to be loaded from theme files.
+    ///
+    /// Properties can be defined with [`define_theme_property!`](crate::define_theme_property).
+    fn register_theme_property<T>(&mut self) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName + DeserializeOwned;
+
+    /// Applies the `T` property to the `C` component of the entities matched by its selectors.
+    ///
+    /// `apply` is called when the matched properties of an entity may have changed, and should only
+    /// modify the component if the value differs to avoid triggering change detection.
+    fn bind_theme_property<T, C>(&mut self, apply: fn(&T, Mut<C>)) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+        C: Component;
+}
+
+impl ThemePropertyAppExt for App {
+    fn register_theme_property<T>(&mut self) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName + DeserializeOwned,
+    {
+        self.world
+            .get_resource_or_insert_with(ThemePropertyRegistry::default)
+            .register::<T>();
+        self
+    }
+
+    fn bind_theme_property<T, C>(&mut self, apply: fn(&T, Mut<C>)) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+        C: Component,
+    {
+        self.add_system_to_stage(
+            CoreStage::PreUpdate,
+            (move |theme: Option<Res<ThemeManager>>,
+                   nodes: ThemeNodes,
+                   mut query: BoundQuery<C>| {
+                let theme = match theme {
+                    Some(theme) if !nodes.is_unchanged(&theme) => theme,
+                    _ => return,
+                };
+                for (entity, component) in query.iter_mut() {
+                    let selectors = match nodes.match_selectors(entity, &theme, false, false) {
+                        Some(selectors) => selectors,
+                        None => continue,
+                    };
+                    if let Some(property) = selectors.get_property::<T>() {
+                        apply(property, component);
+                    }
+                }
+            })
+            .label(ThemingSystem::Apply)
+            .after(ThemingSystem::Match),
+        )
+    }
+}
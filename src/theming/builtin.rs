@@ -0,0 +1,168 @@
+use bevy_render::prelude::*;
+
+use super::{
+    BorderColorProperty, ColorProperty, TextColorProperty, Theme, ThemeManager, ThemeVariable,
+    VariableRef,
+};
+
+/// Light or dark variant of the built-in theme.
+///
+/// Both variants use the same properties and only differ by the value of the palette variables,
+/// so switching variants with [`ThemeManager::set_builtin_variant`] keeps properties set by the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeVariant {
+    Light,
+    Dark,
+}
+
+impl Default for ThemeVariant {
+    fn default() -> Self {
+        Self::Dark
+    }
+}
+
+impl ThemeVariant {
+    /// Returns the variables referenced by the built-in theme:
+    ///
+    /// - `background`: background of frames
+    /// - `surface`: background of title bars, buttons and tooltips
+    /// - `control`: background of slider tracks, scrollbars and spin box buttons
+    /// - `accent`: colour of slider and scrollbar thumbs
+    /// - `border`: colour of frame and tooltip borders
+    /// - `text`: colour of widget texts
+    /// - `muted`: colour of placeholder texts
+    pub fn palette(&self) -> [(&'static str, ThemeVariable); 7] {
+        let (background, surface, control, accent, border, text, muted) = match self {
+            ThemeVariant::Light => (
+                Color::rgb(0.95, 0.95, 0.95),
+                Color::rgb(0.85, 0.85, 0.85),
+                Color::rgb(0.75, 0.75, 0.75),
+                Color::rgb(0.2, 0.4, 0.75),
+                Color::rgb(0.6, 0.6, 0.6),
+                Color::rgb(0.1, 0.1, 0.1),
+                Color::rgb(0.45, 0.45, 0.45),
+            ),
+            ThemeVariant::Dark => (
+                Color::rgb(0.05, 0.05, 0.05),
+                Color::rgb(0.15, 0.15, 0.15),
+                Color::rgb(0.25, 0.25, 0.25),
+                Color::rgb(0.35, 0.55, 0.85),
+                Color::rgb(0.3, 0.3, 0.3),
+                Color::rgb(0.9, 0.9, 0.9),
+                Color::rgb(0.55, 0.55, 0.55),
+            ),
+        };
+        [
+            ("background", ThemeVariable::Color(background)),
+            ("surface", ThemeVariable::Color(surface)),
+            ("control", ThemeVariable::Color(control)),
+            ("accent", ThemeVariable::Color(accent)),
+            ("border", ThemeVariable::Color(border)),
+            ("text", ThemeVariable::Color(text)),
+            ("muted", ThemeVariable::Color(muted)),
+        ]
+    }
+}
+
+impl Theme {
+    /// Creates the built-in theme, which colours every part of the crate's widgets.
+    ///
+    /// It is only active when opted in with [`ThemeManager::with_builtin`], since its properties
+    /// override the colours set on widget bundles. Widget parts are matched by the name of their
+    /// [theme marker](super::ThemeMarkers), e.g. `"SliderThumbNode"`, so properties of the built-in
+    /// theme can be overridden with more specific selectors or by setting the same selector.
+    /// Custom themes can start from this one to keep widgets styled.
+    ///
+    /// Only colours and decorations are set. Sizes and spacing are out of scope of the built-in
+    /// theme and stay defaults of the widget builders, which can be overridden by any theme.
+    pub fn builtin(variant: ThemeVariant) -> Self {
+        let mut theme = Theme::new();
+        for (name, value) in variant.palette() {
+            theme.set_variable(name, value);
+        }
+        let var = VariableRef::new;
+
+        // Frame
+        theme
+            .set_property_ref::<ColorProperty>("Frame", var("background"))
+            .set_property_ref::<BorderColorProperty>("Frame", var("border"))
+            .set_property_ref::<ColorProperty>("FrameGrabber", var("surface"))
+            .set_property_ref::<TextColorProperty>("FrameTitleText", var("text"))
+            .set_property_ref::<ColorProperty>("FrameCloseButton", var("surface"))
+            .set_property_ref::<ColorProperty>(
+                "FrameCloseButton:hover",
+                var("surface").lighten(0.05),
+            )
+            .set_property_ref::<ColorProperty>(
+                "FrameCloseButton:pressed",
+                var("surface").darken(0.05),
+            );
+
+        // Slider
+        theme
+            .set_property_ref::<ColorProperty>("SliderTrackNode", var("control"))
+            .set_property_ref::<ColorProperty>("SliderThumbNode", var("accent"))
+            .set_property_ref::<ColorProperty>("SliderThumbNode:hover", var("accent").lighten(0.05))
+            .set_property_ref::<ColorProperty>(
+                "SliderThumbNode:pressed",
+                var("accent").darken(0.05),
+            )
+            .set_property_ref::<TextColorProperty>("SliderValueLabelNode", var("text"));
+
+        // Scroll view
+        theme
+            .set_property_ref::<ColorProperty>("ScrollbarTrack", var("control"))
+            .set_property_ref::<ColorProperty>("ScrollbarThumb", var("accent"))
+            .set_property_ref::<ColorProperty>("ScrollbarThumb:hover", var("accent").lighten(0.05))
+            .set_property_ref::<ColorProperty>(
+                "ScrollbarThumb:pressed",
+                var("accent").darken(0.05),
+            );
+
+        // Text input
+        theme
+            .set_property_ref::<ColorProperty>("TextInput", var("surface"))
+            .set_property_ref::<BorderColorProperty>("TextInput", var("border"))
+            .set_property_ref::<BorderColorProperty>("TextInput:focused", var("accent"))
+            .set_property_ref::<TextColorProperty>("TextInputText", var("text"))
+            .set_property_ref::<TextColorProperty>("TextInputText::selection", var("accent"))
+            .set_property_ref::<TextColorProperty>("TextInputText::caret", var("accent"))
+            .set_property_ref::<TextColorProperty>("TextInputText::placeholder", var("muted"));
+
+        // Spin box, whose field is also styled as a text input.
+        theme
+            .set_property_ref::<TextColorProperty>("SpinBoxLabel", var("text"))
+            .set_property_ref::<ColorProperty>("SpinBoxButton", var("control"))
+            .set_property_ref::<ColorProperty>("SpinBoxButton:hover", var("control").lighten(0.05))
+            .set_property_ref::<ColorProperty>("SpinBoxButton:pressed", var("accent"))
+            .set_property_ref::<TextColorProperty>("SpinBoxButtonText", var("text"));
+
+        // Tooltip, whose text is styled by the user.
+        theme
+            .set_property_ref::<BorderColorProperty>("Tooltip", var("border"))
+            .set_property_ref::<ColorProperty>("Tooltip", var("surface"))
+            .set_property_ref::<ColorProperty>("TooltipArrowUiNode", var("surface"));
+
+        // Theme inspector
+        theme
+            .set_property_ref::<TextColorProperty>("ThemeInspectorText", var("text"))
+            .set_property_ref::<ColorProperty>("ThemeInspectorStepperButton", var("surface"))
+            .set_property_ref::<ColorProperty>(
+                "ThemeInspectorStepperButton:hover",
+                var("surface").lighten(0.05),
+            );
+
+        theme
+    }
+}
+
+impl ThemeManager {
+    /// Sets the palette variables of the built-in theme in the active theme, e.g. to switch
+    /// between light and dark mode.
+    pub fn set_builtin_variant(&mut self, variant: ThemeVariant) -> &mut Self {
+        for (name, value) in variant.palette() {
+            self.set_variable(name, value);
+        }
+        self
+    }
+}
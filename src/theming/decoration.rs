@@ -0,0 +1,276 @@
+use bevy_asset::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_render::prelude::*;
+use bevy_ui::{prelude::*, FocusPolicy};
+use bevy_utils::default;
+
+use super::{
+    BorderColorProperty, BorderImage, BorderImageProperty, ThemeKey, ThemeManager, ThemeMarkers,
+    ThemeNodes,
+};
+
+/// Child nodes drawing the border colour and border image of an entity.
+///
+/// This is added automatically when one of these properties is applied.
+#[derive(Component, Default)]
+pub struct ThemeDecorations {
+    border: Option<DecorationGrid>,
+    border_image: Option<DecorationGrid>,
+    /// Values the decorations were last drawn with.
+    drawn: Option<DrawnDecorations>,
+    /// Cells of the last border image, so that its images are only loaded when it changes.
+    border_image_cells: Option<GridCells>,
+}
+
+#[derive(Clone, PartialEq)]
+struct DrawnDecorations {
+    border: UiRect<Val>,
+    border_color: Option<Color>,
+    border_image: Option<BorderImage>,
+}
+
+/// A 3x3 grid of image nodes covering the border box of its parent.
+///
+/// The outer rows and columns have a fixed size while the center cell fills the remaining space,
+/// which is used to draw both borders and nine-slice images.
+#[derive(Clone, Copy)]
+struct DecorationGrid {
+    root: Entity,
+    rows: [Entity; 3],
+    cells: [Entity; 9],
+}
+
+/// Image and colour of each cell of a [`DecorationGrid`], row by row.
+type GridCells = [(UiImage, UiColor); 9];
+
+impl DecorationGrid {
+    /// Spawns the grid as the first child of `parent`, so that it is drawn below its other children.
+    fn spawn(commands: &mut Commands, parent: Entity) -> Self {
+        let mut node = || {
+            commands
+                .spawn_bundle(ImageBundle {
+                    color: Color::NONE.into(),
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                })
+                .id()
+        };
+        let root = node();
+        let rows = [node(), node(), node()];
+        let cells = [(); 9].map(|_| node());
+        commands.entity(root).push_children(&rows);
+        for (row, cells) in rows.iter().zip(cells.chunks(3)) {
+            commands.entity(*row).push_children(cells);
+        }
+        commands.entity(parent).insert_children(0, &[root]);
+        Self { root, rows, cells }
+    }
+
+    /// Sizes the outer rows and columns to `slices`, in a parent with the given `border`.
+    fn update(
+        &self,
+        commands: &mut Commands,
+        border: UiRect<Val>,
+        slices: UiRect<Val>,
+        cells: GridCells,
+    ) {
+        // Absolute positions are relative to the inside of the parent's border.
+        commands.entity(self.root).insert(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: negate(border.left),
+                top: negate(border.top),
+                ..default()
+            },
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            flex_direction: FlexDirection::Column,
+            ..default()
+        });
+        let heights = [Some(slices.top), None, Some(slices.bottom)];
+        let widths = [Some(slices.left), None, Some(slices.right)];
+        for (row, height) in self.rows.iter().zip(heights) {
+            commands
+                .entity(*row)
+                .insert(fixed_or_fill(height, |size| Size::new(Val::Auto, size)));
+        }
+        for (i, (image, color)) in cells.into_iter().enumerate() {
+            let style = fixed_or_fill(widths[i % 3], |size| Size::new(size, Val::Auto));
+            commands
+                .entity(self.cells[i])
+                .insert_bundle((style, image, color));
+        }
+    }
+
+    fn despawn(&self, commands: &mut Commands) {
+        commands.entity(self.root).despawn_recursive();
+    }
+}
+
+/// Style of a grid row or cell, which either has a fixed size along the main axis or fills the
+/// remaining space.
+fn fixed_or_fill(size: Option<Val>, to_size: impl Fn(Val) -> Size<Val>) -> Style {
+    match size {
+        Some(size) => Style {
+            size: to_size(size),
+            flex_shrink: 0.0,
+            ..default()
+        },
+        None => Style {
+            flex_grow: 1.0,
+            flex_basis: Val::Px(0.0),
+            ..default()
+        },
+    }
+}
+
+fn negate(val: Val) -> Val {
+    match val {
+        Val::Px(px) => Val::Px(-px),
+        Val::Percent(percent) => Val::Percent(-percent),
+        _ => Val::Px(0.0),
+    }
+}
+
+/// Cells drawing a border of the given colour, with a transparent center.
+fn border_cells(color: Color) -> GridCells {
+    let mut cells = [(); 9].map(|_| (UiImage::default(), UiColor(color)));
+    cells[4].1 = UiColor(Color::NONE);
+    cells
+}
+
+/// Cells drawing the parts of a nine-slice image, with a transparent center if it has no center image.
+fn border_image_cells(border_image: &BorderImage, asset_server: &AssetServer) -> GridCells {
+    let load = |path: &String| (UiImage(asset_server.load(path)), UiColor(Color::WHITE));
+    [
+        load(&border_image.top_left),
+        load(&border_image.top),
+        load(&border_image.top_right),
+        load(&border_image.left),
+        match &border_image.center {
+            Some(center) => load(center),
+            None => (UiImage::default(), UiColor(Color::NONE)),
+        },
+        load(&border_image.right),
+        load(&border_image.bottom_left),
+        load(&border_image.bottom),
+        load(&border_image.bottom_right),
+    ]
+}
+
+/// Spawns, updates or despawns a grid depending on whether it is still needed.
+fn update_grid(
+    commands: &mut Commands,
+    parent: Entity,
+    grid: Option<DecorationGrid>,
+    border: UiRect<Val>,
+    content: Option<(UiRect<Val>, GridCells)>,
+) -> Option<DecorationGrid> {
+    match (grid, content) {
+        (grid, Some((slices, cells))) => {
+            let grid = grid.unwrap_or_else(|| DecorationGrid::spawn(commands, parent));
+            grid.update(commands, border, slices, cells);
+            Some(grid)
+        }
+        (Some(grid), None) => {
+            grid.despawn(commands);
+            None
+        }
+        (None, None) => None,
+    }
+}
+
+/// Draws the [`BorderColorProperty`] and [`BorderImageProperty`] of entities with child nodes.
+///
+/// Decorations follow the entity's border, so they are also checked when its [`Style`] changes,
+/// but they are only rebuilt when the border or one of these properties changed.
+pub(crate) fn update_decorations(
+    mut commands: Commands,
+    theme: Option<Res<ThemeManager>>,
+    asset_server: Res<AssetServer>,
+    nodes: ThemeNodes,
+    mut query: Query<
+        (
+            Entity,
+            &Style,
+            ChangeTrackers<Style>,
+            Option<&mut ThemeDecorations>,
+        ),
+        Or<(With<ThemeKey>, With<ThemeMarkers>)>,
+    >,
+    changed_styles: Query<(), (Changed<Style>, Or<(With<ThemeKey>, With<ThemeMarkers>)>)>,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    if nodes.is_unchanged(&theme) && changed_styles.is_empty() {
+        return;
+    }
+    for (entity, style, style_tracker, decorations) in query.iter_mut() {
+        let force = style_tracker.is_changed();
+        let selectors = match nodes.match_selectors(entity, &theme, false, force) {
+            Some(selectors) => selectors,
+            None => continue,
+        };
+        let drawn = DrawnDecorations {
+            border: style.border,
+            border_color: selectors
+                .get_property::<BorderColorProperty>()
+                .map(|property| property.0),
+            border_image: selectors
+                .get_property::<BorderImageProperty>()
+                .map(|property| property.0.clone()),
+        };
+        let is_drawn = match &decorations {
+            Some(decorations) => decorations.drawn.as_ref() == Some(&drawn),
+            // Entities without decorations only need them once one of the properties is set.
+            None => drawn.border_color.is_none() && drawn.border_image.is_none(),
+        };
+        if is_drawn {
+            continue;
+        }
+
+        let mut added = ThemeDecorations::default();
+        let is_added = decorations.is_none();
+        let decorations = match decorations {
+            Some(decorations) => decorations.into_inner(),
+            None => &mut added,
+        };
+        let image_changed = decorations
+            .drawn
+            .as_ref()
+            .map_or(true, |previous| previous.border_image != drawn.border_image);
+        if image_changed {
+            decorations.border_image_cells = drawn
+                .border_image
+                .as_ref()
+                .map(|border_image| border_image_cells(border_image, &asset_server));
+        }
+        // The border is spawned first so that the border image, spawned below it, doesn't hide it.
+        decorations.border = update_grid(
+            &mut commands,
+            entity,
+            decorations.border,
+            drawn.border,
+            drawn
+                .border_color
+                .map(|color| (drawn.border, border_cells(color))),
+        );
+        decorations.border_image = update_grid(
+            &mut commands,
+            entity,
+            decorations.border_image,
+            drawn.border,
+            drawn
+                .border_image
+                .as_ref()
+                .zip(decorations.border_image_cells.clone())
+                .map(|(border_image, cells)| (border_image.slices, cells)),
+        );
+        decorations.drawn = Some(drawn);
+        if is_added {
+            commands.entity(entity).insert(added);
+        }
+    }
+}
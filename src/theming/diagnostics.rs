@@ -0,0 +1,311 @@
+use std::fmt;
+
+use bevy_app::prelude::*;
+use bevy_asset::{prelude::*, LoadState};
+use bevy_ecs::prelude::*;
+use bevy_utils::{tracing::warn, HashSet};
+
+use super::{
+    Theme, ThemeKey, ThemeLoadErrors, ThemeManager, ThemeMarkers, ThemeNodeQuery, ThemeVariant,
+};
+
+/// Reports mistakes in themes and theme keys, such as typos in classes or references to missing
+/// variables.
+///
+/// Issues are collected in the [`ThemeReport`] resource and logged as warnings when they first
+/// appear. This is meant to be used during development, since checking every selector against
+/// every entity can be slow.
+pub struct ThemeDiagnosticsPlugin;
+
+impl Plugin for ThemeDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ThemeReport>()
+            .add_system_to_stage(CoreStage::Last, update_theme_report);
+    }
+}
+
+/// Issues found in the active theme and the entities it applies to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeReport {
+    pub issues: Vec<ThemeIssue>,
+}
+
+/// A mistake found by the [`ThemeDiagnosticsPlugin`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ThemeIssue {
+    /// A class of a [`ThemeKey`] that no selector of the active theme uses.
+    UnknownClass(String),
+    /// An id of a [`ThemeKey`] that no selector of the active theme uses.
+    UnknownId(String),
+    /// A selector of the active theme that matches no entity, whatever its state.
+    UnusedSelector(String),
+    /// A state of a selector of the active theme that isn't a
+    /// [`SelectorState`](super::SelectorState), so the selector never matches.
+    UnknownState { selector: String, state: String },
+    /// A property referencing a variable that the active theme doesn't have.
+    MissingVariable {
+        selector: String,
+        property: String,
+        variable: String,
+    },
+    /// A property referencing a variable whose value, once modified, can't be used for the property.
+    MismatchedVariable {
+        selector: String,
+        property: String,
+        variable: String,
+    },
+    /// A theme source asset that failed to load, e.g. because of an unknown property name or a
+    /// syntax error.
+    FailedSource { theme: String, path: String },
+    /// A property of a theme source asset whose value has the wrong type, which prevented the
+    /// source from loading.
+    InvalidValue {
+        theme: String,
+        path: String,
+        property: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for ThemeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownClass(class) => {
+                write!(f, "class `{}` is not used by any selector", class)
+            }
+            Self::UnknownId(id) => write!(f, "id `{}` is not used by any selector", id),
+            Self::UnusedSelector(selector) => {
+                write!(f, "selector `{}` doesn't match any entity", selector)
+            }
+            Self::UnknownState { selector, state } => {
+                write!(f, "selector `{}` uses unknown state `:{}`", selector, state)
+            }
+            Self::MissingVariable {
+                selector,
+                property,
+                variable,
+            } => write!(
+                f,
+                "`{}:{}` references missing variable `{}`",
+                selector, property, variable
+            ),
+            Self::MismatchedVariable {
+                selector,
+                property,
+                variable,
+            } => write!(
+                f,
+                "`{}:{}` references variable `{}` with an incompatible type",
+                selector, property, variable
+            ),
+            Self::FailedSource { theme, path } => {
+                write!(f, "source `{}` of theme `{}` failed to load", path, theme)
+            }
+            Self::InvalidValue {
+                theme,
+                path,
+                property,
+                error,
+            } => write!(
+                f,
+                "invalid value for `{}` in source `{}` of theme `{}`: {}",
+                property, path, theme, error
+            ),
+        }
+    }
+}
+
+impl ThemeReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn check_keys(&mut self, theme: &ThemeManager, keys: impl Iterator<Item = &ThemeKey>) {
+        let selectors: Vec<_> = theme
+            .active_theme()
+            .selectors()
+            .map(|(_, _, selector)| selector)
+            .collect();
+        let classes: HashSet<_> = selectors.iter().flat_map(|s| s.classes()).collect();
+        let ids: HashSet<_> = selectors.iter().flat_map(|s| s.ids()).collect();
+        let mut unknown = HashSet::default();
+        for key in keys {
+            for class in key
+                .classes
+                .iter()
+                .filter(|class| !classes.contains(class.as_str()))
+            {
+                unknown.insert(ThemeIssue::UnknownClass(class.clone()));
+            }
+            if let Some(id) = key.id.as_ref().filter(|id| !ids.contains(id.as_str())) {
+                unknown.insert(ThemeIssue::UnknownId(id.clone()));
+            }
+        }
+        self.issues.extend(unknown);
+    }
+
+    fn check_selectors(
+        &mut self,
+        theme: &ThemeManager,
+        builtin: &BuiltinSelectors,
+        entities: &[Entity],
+        query: &ThemeNodeQuery,
+    ) {
+        for (_, key, selector) in theme.active_theme().selectors() {
+            if !builtin.0.contains(key)
+                && !entities
+                    .iter()
+                    .any(|entity| selector.can_match(*entity, query))
+            {
+                self.issues
+                    .push(ThemeIssue::UnusedSelector(key.to_string()));
+            }
+        }
+    }
+
+    fn check_states(&mut self, theme: &ThemeManager) {
+        for (_, key, selector) in theme.active_theme().selectors() {
+            for state in selector.unknown_states() {
+                self.issues.push(ThemeIssue::UnknownState {
+                    selector: key.to_string(),
+                    state: state.to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_references(&mut self, theme: &ThemeManager) {
+        let theme = theme.active_theme();
+        for (selector, property, reference) in theme.references_with_resolvers() {
+            let variable = &reference.reference.variable;
+            let issue = match theme.get_variable(variable) {
+                None => ThemeIssue::MissingVariable {
+                    selector: selector.to_string(),
+                    property: property.to_string(),
+                    variable: variable.clone(),
+                },
+                Some(value)
+                    if reference
+                        .reference
+                        .resolve(value)
+                        .and_then(|value| (reference.resolve)(&value))
+                        .is_none() =>
+                {
+                    ThemeIssue::MismatchedVariable {
+                        selector: selector.to_string(),
+                        property: property.to_string(),
+                        variable: variable.clone(),
+                    }
+                }
+                Some(_) => continue,
+            };
+            self.issues.push(issue);
+        }
+    }
+
+    fn check_sources(
+        &mut self,
+        theme: &ThemeManager,
+        asset_server: &AssetServer,
+        errors: Option<&ThemeLoadErrors>,
+    ) {
+        for name in theme.theme_names() {
+            let handle = match theme.theme_source(name) {
+                Some(handle) => handle,
+                None => continue,
+            };
+            if asset_server.get_load_state(handle) != LoadState::Failed {
+                continue;
+            }
+            let asset_path = asset_server.get_handle_path(handle);
+            let path = asset_path
+                .as_ref()
+                .map_or_else(String::new, |path| path.path().display().to_string());
+            let invalid_property = asset_path
+                .as_ref()
+                .and_then(|path| errors?.get(path.path()))
+                .and_then(|error| error.invalid_property);
+            self.issues.push(match invalid_property {
+                Some((property, error)) => ThemeIssue::InvalidValue {
+                    theme: name.to_string(),
+                    path,
+                    property,
+                    error,
+                },
+                None => ThemeIssue::FailedSource {
+                    theme: name.to_string(),
+                    path,
+                },
+            });
+        }
+    }
+}
+
+/// Selectors of the built-in theme, which are collected once.
+///
+/// They style every widget, so they are expected to be unused when the app doesn't spawn some
+/// widgets.
+struct BuiltinSelectors(HashSet<String>);
+
+impl Default for BuiltinSelectors {
+    fn default() -> Self {
+        // Both variants only differ by their variables, so they have the same selectors.
+        let builtin = Theme::builtin(ThemeVariant::default());
+        Self(
+            builtin
+                .selectors()
+                .map(|(_, key, _)| key.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// Checks the active theme again when it or the theme keys and markers of entities change.
+fn update_theme_report(
+    theme: Option<Res<ThemeManager>>,
+    asset_server: Res<AssetServer>,
+    mut report: ResMut<ThemeReport>,
+    mut cached: Local<Option<ThemeReport>>,
+    builtin: Local<BuiltinSelectors>,
+    errors: Option<Res<ThemeLoadErrors>>,
+    entities: Query<Entity, Or<(With<ThemeKey>, With<ThemeMarkers>)>>,
+    changed: Query<(), Or<(Changed<ThemeKey>, Changed<ThemeMarkers>)>>,
+    removed_keys: RemovedComponents<ThemeKey>,
+    removed_markers: RemovedComponents<ThemeMarkers>,
+    keys: Query<&ThemeKey>,
+    query: ThemeNodeQuery,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    // Checking keys and selectors is the slow part, so it is only done when something changed.
+    let changed = theme.is_changed()
+        || !changed.is_empty()
+        || removed_keys.iter().next().is_some()
+        || removed_markers.iter().next().is_some();
+    if changed || cached.is_none() {
+        let mut checked = ThemeReport::default();
+        checked.check_keys(&theme, keys.iter());
+        let entities: Vec<_> = entities.iter().collect();
+        checked.check_selectors(&theme, &builtin, &entities, &query);
+        checked.check_states(&theme);
+        checked.check_references(&theme);
+        // Issues come from hash maps, so they are sorted to keep the report stable.
+        checked.issues.sort_by_cached_key(ToString::to_string);
+        *cached = Some(checked);
+    }
+
+    let mut new_report = cached.clone().unwrap_or_default();
+    new_report.check_sources(&theme, &asset_server, errors.as_deref());
+    if *report != new_report {
+        for issue in new_report
+            .issues
+            .iter()
+            .filter(|issue| !report.issues.contains(issue))
+        {
+            warn!("theme issue: {}", issue);
+        }
+        *report = new_report;
+    }
+}
@@ -0,0 +1,267 @@
+use std::{collections::BTreeMap, fmt, io};
+
+use ron::ser::PrettyConfig;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use super::Theme;
+
+/// Themes are serialized in the format read by the [`ThemeLoader`](super::ThemeLoader), so that
+/// exported themes can be loaded back as assets.
+///
+/// Entries are sorted by key to keep the output stable between exports.
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let properties: BTreeMap<_, _> = self
+            .iter()
+            .map(|(selector, name, property)| {
+                (format!("{}:{}", selector, name), property.as_serialize())
+            })
+            .collect();
+        let variables: BTreeMap<_, _> = self.variables().collect();
+        let references: BTreeMap<_, _> = self
+            .references()
+            .map(|(selector, name, reference)| (format!("{}:{}", selector, name), reference))
+            .collect();
+
+        let mut theme = serializer.serialize_struct("Theme", 3)?;
+        theme.serialize_field("properties", &properties)?;
+        theme.serialize_field("variables", &variables)?;
+        theme.serialize_field("references", &references)?;
+        theme.end()
+    }
+}
+
+impl Theme {
+    /// Serializes the theme to the contents of a `.theme.ron` file.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+    }
+}
+
+/// An error that occurred while saving a theme with [`ThemeManager::save_theme`](super::ThemeManager::save_theme).
+#[derive(Debug)]
+pub enum ThemeExportError {
+    /// The [`ThemeManager`](super::ThemeManager) has no theme with this name.
+    UnknownTheme(String),
+    /// A property failed to serialize.
+    Serialize(ron::Error),
+    /// The file couldn't be written.
+    Io(io::Error),
+}
+
+impl fmt::Display for ThemeExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTheme(name) => write!(f, "unknown theme `{}`", name),
+            Self::Serialize(error) => write!(f, "failed to serialize theme: {}", error),
+            Self::Io(error) => write!(f, "failed to write theme: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ThemeExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnknownTheme(_) => None,
+            Self::Serialize(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use bevy_render::prelude::*;
+    use bevy_text::prelude::*;
+    use bevy_ui::prelude::*;
+
+    use super::*;
+    use crate::theming::*;
+
+    /// Creates a theme with every built-in property, including selectors with pseudo-classes.
+    fn full_theme() -> Theme {
+        let slices = UiRect {
+            left: Val::Px(4.0),
+            right: Val::Percent(10.0),
+            top: Val::Auto,
+            bottom: Val::Undefined,
+        };
+        let size = Size::new(Val::Px(100.0), Val::Percent(50.0));
+        let transition = Transition {
+            duration: 0.25,
+            easing: Easing::EaseInOut,
+            ..Default::default()
+        };
+        let mut theme = Theme::new();
+        theme
+            .set_property("node", DisplayProperty(Display::None))
+            .set_property("node", PositionTypeProperty(PositionType::Absolute))
+            .set_property("node", DirectionProperty(Direction::RightToLeft))
+            .set_property("node", FlexDirectionProperty(FlexDirection::ColumnReverse))
+            .set_property("node", FlexWrapProperty(FlexWrap::WrapReverse))
+            .set_property("node", AlignItemsProperty(AlignItems::Baseline))
+            .set_property("node", AlignSelfProperty(AlignSelf::FlexEnd))
+            .set_property("node", AlignContentProperty(AlignContent::SpaceAround))
+            .set_property("node", JustifyContentProperty(JustifyContent::SpaceEvenly))
+            .set_property("node", PositionProperty(slices))
+            .set_property("node", MarginProperty(UiRect::all(Val::Px(5.0))))
+            .set_property("node", PaddingProperty(slices))
+            .set_property("node", BorderProperty(UiRect::all(Val::Percent(1.0))))
+            .set_property("node", FlexGrowProperty(2.0))
+            .set_property("node", FlexShrinkProperty(0.5))
+            .set_property("node", FlexBasisProperty(Val::Percent(25.0)))
+            .set_property("node", SizeProperty(size))
+            .set_property("node", MinSizeProperty(Size::new(Val::Auto, Val::Px(10.0))))
+            .set_property("node", MaxSizeProperty(size))
+            .set_property("node", AspectRatioProperty(Some(1.5)))
+            .set_property("node", OverflowProperty(Overflow::Hidden))
+            .set_property(
+                "text",
+                TextColorProperty(Color::hsla(120.0, 0.5, 0.25, 0.75)),
+            )
+            .set_property(
+                "text",
+                TextFontPathProperty("fonts/FiraSans-Bold.ttf".to_string()),
+            )
+            .set_property("text", TextFontSizeProperty(18.0))
+            .set_property("text", TextHorizontalAlignProperty(HorizontalAlign::Right))
+            .set_property("text", TextVerticalAlignProperty(VerticalAlign::Bottom))
+            .set_property(
+                "button:hover",
+                ColorProperty(Color::rgba(0.1, 0.2, 0.3, 0.4)),
+            )
+            .set_property("button", BackgroundImageProperty("button.png".to_string()))
+            .set_property("button", BorderColorProperty(Color::rgb(0.5, 0.5, 0.5)))
+            .set_property(
+                "button",
+                BorderImageProperty(BorderImage {
+                    slices,
+                    top_left: "top_left.png".to_string(),
+                    top: "top.png".to_string(),
+                    top_right: "top_right.png".to_string(),
+                    left: "left.png".to_string(),
+                    center: Some("center.png".to_string()),
+                    right: "right.png".to_string(),
+                    bottom_left: "bottom_left.png".to_string(),
+                    bottom: "bottom.png".to_string(),
+                    bottom_right: "bottom_right.png".to_string(),
+                }),
+            )
+            .set_property(
+                "button",
+                TransitionProperty(
+                    [
+                        (ColorProperty::PROPERTY_NAME.to_string(), transition.clone()),
+                        (SizeProperty::PROPERTY_NAME.to_string(), transition),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            )
+            .set_variable("primary", ThemeVariable::Color(Color::rgb(0.2, 0.4, 0.75)))
+            .set_variable("spacing", ThemeVariable::Number(10.0))
+            .set_variable("width", ThemeVariable::Val(Val::Percent(80.0)))
+            .set_variable("font", ThemeVariable::String("fonts/font.ttf".to_string()))
+            .set_property_ref::<ColorProperty>("panel", VariableRef::new("primary"))
+            .set_property_ref::<ColorProperty>(
+                "panel:hover",
+                VariableRef::new("primary").lighten(0.05).alpha(0.5),
+            )
+            .set_property_ref::<MarginProperty>("panel", VariableRef::new("spacing").scale(2.0))
+            .set_property_ref::<FlexBasisProperty>("panel", VariableRef::new("width"))
+            .set_property_ref::<TextFontPathProperty>("label", VariableRef::new("font"));
+        theme
+    }
+
+    fn load(ron: &str) -> Theme {
+        load_theme(&ThemePropertyRegistry::default(), ron.as_bytes()).unwrap()
+    }
+
+    /// Properties don't implement `PartialEq`, so they are compared by their debug output.
+    fn assert_property_eq<T: ThemeProperty + ThemePropertyName + Debug>(
+        expected: &Theme,
+        loaded: &Theme,
+        selector: &str,
+    ) {
+        let expected = expected.get_property::<T>(selector);
+        assert!(
+            expected.is_some(),
+            "{} is not set on `{}`",
+            T::PROPERTY_NAME,
+            selector
+        );
+        assert_eq!(
+            format!("{:?}", expected),
+            format!("{:?}", loaded.get_property::<T>(selector)),
+            "{} differs on `{}`",
+            T::PROPERTY_NAME,
+            selector
+        );
+    }
+
+    #[test]
+    fn exported_theme_loads_back() {
+        let theme = full_theme();
+        let loaded = load(&theme.to_ron().unwrap());
+
+        assert_property_eq::<DisplayProperty>(&theme, &loaded, "node");
+        assert_property_eq::<PositionTypeProperty>(&theme, &loaded, "node");
+        assert_property_eq::<DirectionProperty>(&theme, &loaded, "node");
+        assert_property_eq::<FlexDirectionProperty>(&theme, &loaded, "node");
+        assert_property_eq::<FlexWrapProperty>(&theme, &loaded, "node");
+        assert_property_eq::<AlignItemsProperty>(&theme, &loaded, "node");
+        assert_property_eq::<AlignSelfProperty>(&theme, &loaded, "node");
+        assert_property_eq::<AlignContentProperty>(&theme, &loaded, "node");
+        assert_property_eq::<JustifyContentProperty>(&theme, &loaded, "node");
+        assert_property_eq::<PositionProperty>(&theme, &loaded, "node");
+        assert_property_eq::<MarginProperty>(&theme, &loaded, "node");
+        assert_property_eq::<PaddingProperty>(&theme, &loaded, "node");
+        assert_property_eq::<BorderProperty>(&theme, &loaded, "node");
+        assert_property_eq::<FlexGrowProperty>(&theme, &loaded, "node");
+        assert_property_eq::<FlexShrinkProperty>(&theme, &loaded, "node");
+        assert_property_eq::<FlexBasisProperty>(&theme, &loaded, "node");
+        assert_property_eq::<SizeProperty>(&theme, &loaded, "node");
+        assert_property_eq::<MinSizeProperty>(&theme, &loaded, "node");
+        assert_property_eq::<MaxSizeProperty>(&theme, &loaded, "node");
+        assert_property_eq::<AspectRatioProperty>(&theme, &loaded, "node");
+        assert_property_eq::<OverflowProperty>(&theme, &loaded, "node");
+        assert_property_eq::<TextColorProperty>(&theme, &loaded, "text");
+        assert_property_eq::<TextFontPathProperty>(&theme, &loaded, "text");
+        assert_property_eq::<TextFontSizeProperty>(&theme, &loaded, "text");
+        assert_property_eq::<TextHorizontalAlignProperty>(&theme, &loaded, "text");
+        assert_property_eq::<TextVerticalAlignProperty>(&theme, &loaded, "text");
+        assert_property_eq::<ColorProperty>(&theme, &loaded, "button:hover");
+        assert_property_eq::<BackgroundImageProperty>(&theme, &loaded, "button");
+        assert_property_eq::<BorderColorProperty>(&theme, &loaded, "button");
+        assert_property_eq::<BorderImageProperty>(&theme, &loaded, "button");
+        assert_property_eq::<TransitionProperty>(&theme, &loaded, "button");
+
+        for (name, value) in theme.variables() {
+            assert_eq!(
+                loaded.get_variable(name),
+                Some(value),
+                "variable `{}`",
+                name
+            );
+        }
+        assert_eq!(loaded.variables().count(), theme.variables().count());
+        let references = |theme: &Theme| {
+            let mut references: Vec<_> = theme
+                .references()
+                .map(|(selector, name, reference)| (selector.to_string(), name, reference.clone()))
+                .collect();
+            references.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+            references
+        };
+        assert_eq!(references(&loaded), references(&theme));
+    }
+
+    #[test]
+    fn export_is_stable() {
+        let ron = full_theme().to_ron().unwrap();
+        assert_eq!(load(&ron).to_ron().unwrap(), ron);
+        assert_eq!(full_theme().to_ron().unwrap(), ron);
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    any::TypeId,
+    cell::Cell,
+    collections::HashMap as StdHashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use bevy_asset::{AssetLoader, BoxedFuture, Error, LoadContext, LoadedAsset};
+use bevy_ecs::world::{FromWorld, World};
+use bevy_utils::HashMap;
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use super::{
+    property_resolver, register_builtin_properties, PropertyReference, ResolvePropertyFn, Theme,
+    ThemeProperty, ThemePropertyName, ThemeVariable, VariableRef,
+};
+
+type DeserializePropertyFn =
+    fn(&mut dyn erased_serde::Deserializer) -> Result<Box<dyn ThemeProperty>, erased_serde::Error>;
+
+/// Maps property names to the functions used to create them from theme files.
+///
+/// The registry is stored as a resource and shared with the [`ThemeLoader`], so that properties
+/// registered with [`ThemePropertyAppExt`](super::ThemePropertyAppExt) can be loaded from files.
+#[derive(Clone)]
+pub(crate) struct ThemePropertyRegistry {
+    properties: Arc<RwLock<HashMap<&'static str, RegisteredProperty>>>,
+}
+
+#[derive(Clone, Copy)]
+struct RegisteredProperty {
+    deserialize: DeserializePropertyFn,
+    resolve: ResolvePropertyFn,
+    type_id: TypeId,
+}
+
+impl Default for ThemePropertyRegistry {
+    fn default() -> Self {
+        let registry = Self {
+            properties: Arc::default(),
+        };
+        register_builtin_properties(&registry);
+        registry
+    }
+}
+
+impl ThemePropertyRegistry {
+    pub(crate) fn register<T>(&self) -> &Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName + DeserializeOwned,
+    {
+        self.properties.write().unwrap().insert(
+            T::PROPERTY_NAME,
+            RegisteredProperty {
+                deserialize: |deserializer| {
+                    Ok(Box::new(erased_serde::deserialize::<T>(deserializer)?))
+                },
+                resolve: property_resolver::<T>(),
+                type_id: TypeId::of::<T>(),
+            },
+        );
+        self
+    }
+
+    /// Splits a full property name into its selector and property name, and finds the property.
+    fn get<'n, E: de::Error>(
+        &self,
+        full_name: &'n str,
+    ) -> Result<(&'n str, &'n str, RegisteredProperty), E> {
+        let (selector, property_name) = match full_name.rsplit_once(':') {
+            Some(names) => names,
+            None => {
+                return Err(E::custom(format!(
+                    "invalid property `{}`, expected `<selector>:<property name>`",
+                    full_name
+                )))
+            }
+        };
+        match self.properties.read().unwrap().get(property_name) {
+            Some(property) => Ok((selector, property_name, *property)),
+            None => Err(E::custom(format!(
+                "unknown property name `{}` in `{}`",
+                property_name, full_name
+            ))),
+        }
+    }
+}
+
+/// Loads [`Theme`] assets from `.theme.ron` files.
+///
+/// A theme file contains a map of properties where keys are formatted as `"<selector>:<property name>"`,
+/// see [`Selector`](super::Selector) for the selector syntax:
+///
+/// ```ron
+/// (
+///     properties: {
+///         "button:background-color": Rgba(red: 0.15, green: 0.15, blue: 0.15, alpha: 1.0),
+///         "button:padding": (left: Px(10.0), right: Px(10.0), top: Px(10.0), bottom: Px(10.0)),
+///         "frame .title:font-size": 20.0,
+///     },
+/// )
+/// ```
+///
+/// Values shared by many properties can be declared as [variables](ThemeVariable) and used by
+/// properties in the `references` map, with optional [modifiers](super::ValueModifier):
+///
+/// ```ron
+/// (
+///     variables: {
+///         "primary": Color(Rgba(red: 0.15, green: 0.15, blue: 0.15, alpha: 1.0)),
+///         "spacing": Number(10.0),
+///     },
+///     references: {
+///         "button:background-color": (variable: "primary"),
+///         "button:hover:background-color": (variable: "primary", modifiers: [Lighten(0.05)]),
+///         "button:padding": (variable: "spacing"),
+///     },
+/// )
+/// ```
+///
+/// Themes can be saved in this format with [`ThemeManager::save_theme`](super::ThemeManager::save_theme).
+pub struct ThemeLoader {
+    registry: ThemePropertyRegistry,
+    errors: ThemeLoadErrors,
+}
+
+impl FromWorld for ThemeLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            registry: world
+                .get_resource_or_insert_with(ThemePropertyRegistry::default)
+                .clone(),
+            errors: world
+                .get_resource_or_insert_with(ThemeLoadErrors::default)
+                .clone(),
+        }
+    }
+}
+
+impl AssetLoader for ThemeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let result = load_theme(&self.registry, bytes);
+            self.errors
+                .record(load_context.path(), result.as_ref().err());
+            load_context.set_default_asset(LoadedAsset::new(result?));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+/// Deserializes the contents of a `.theme.ron` file.
+pub(crate) fn load_theme(
+    registry: &ThemePropertyRegistry,
+    bytes: &[u8],
+) -> Result<Theme, ThemeLoadError> {
+    let invalid_property = Cell::new(None);
+    let load = || -> ron::Result<Theme> {
+        let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+        let theme = ThemeSeed {
+            registry,
+            invalid_property: &invalid_property,
+        }
+        .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(theme)
+    };
+    load().map_err(|error| ThemeLoadError {
+        message: error.to_string(),
+        invalid_property: invalid_property.take(),
+    })
+}
+
+/// An error that occurred while loading a theme file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ThemeLoadError {
+    pub(crate) message: String,
+    /// Full name of the property whose value failed to deserialize, and the reason why.
+    pub(crate) invalid_property: Option<(String, String)>,
+}
+
+impl fmt::Display for ThemeLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ThemeLoadError {}
+
+/// Errors of the theme files that failed to load, by asset path.
+///
+/// The asset server only tells that a load failed, so the [`ThemeLoader`] shares its errors with
+/// the [`ThemeDiagnosticsPlugin`](super::ThemeDiagnosticsPlugin) through this resource.
+#[derive(Clone, Default)]
+pub(crate) struct ThemeLoadErrors {
+    errors: Arc<RwLock<StdHashMap<PathBuf, ThemeLoadError>>>,
+}
+
+impl ThemeLoadErrors {
+    pub(crate) fn get(&self, path: &Path) -> Option<ThemeLoadError> {
+        self.errors.read().unwrap().get(path).cloned()
+    }
+
+    /// Records the error of the last load of a file, or removes it if the file loaded.
+    fn record(&self, path: &Path, error: Option<&ThemeLoadError>) {
+        let mut errors = self.errors.write().unwrap();
+        match error {
+            Some(error) => {
+                errors.insert(path.to_path_buf(), error.clone());
+            }
+            None => {
+                errors.remove(path);
+            }
+        }
+    }
+}
+
+const THEME_FIELDS: &[&str] = &["properties", "variables", "references"];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum ThemeField {
+    Properties,
+    Variables,
+    References,
+}
+
+struct ThemeSeed<'a> {
+    registry: &'a ThemePropertyRegistry,
+    invalid_property: &'a Cell<Option<(String, String)>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for ThemeSeed<'a> {
+    type Value = Theme;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("Theme", THEME_FIELDS, self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for ThemeSeed<'a> {
+    type Value = Theme;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a theme")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut theme = Theme::new();
+        while let Some(field) = map.next_key::<ThemeField>()? {
+            match field {
+                ThemeField::Properties => map.next_value_seed(PropertiesSeed {
+                    registry: self.registry,
+                    invalid_property: self.invalid_property,
+                    theme: &mut theme,
+                    references: false,
+                })?,
+                ThemeField::Variables => {
+                    for (name, value) in map.next_value::<StdHashMap<String, ThemeVariable>>()? {
+                        theme.set_variable(&name, value);
+                    }
+                }
+                ThemeField::References => map.next_value_seed(PropertiesSeed {
+                    registry: self.registry,
+                    invalid_property: self.invalid_property,
+                    theme: &mut theme,
+                    references: true,
+                })?,
+            }
+        }
+        Ok(theme)
+    }
+}
+
+struct PropertiesSeed<'a> {
+    registry: &'a ThemePropertyRegistry,
+    invalid_property: &'a Cell<Option<(String, String)>>,
+    theme: &'a mut Theme,
+    /// Whether values are [`VariableRef`]s instead of property values.
+    references: bool,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PropertiesSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for PropertiesSeed<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of theme properties")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while let Some(full_name) = map.next_key::<String>()? {
+            let (selector, property_name, registered) = self.registry.get(&full_name)?;
+            if self.references {
+                let reference = PropertyReference {
+                    reference: map.next_value::<VariableRef>()?,
+                    resolve: registered.resolve,
+                    type_id: registered.type_id,
+                };
+                self.theme
+                    .insert_reference(selector, property_name, reference);
+            } else {
+                let property = map.next_value_seed(PropertySeed {
+                    deserialize: registered.deserialize,
+                    full_name: &full_name,
+                    invalid_property: self.invalid_property,
+                })?;
+                self.theme.insert_boxed(selector, property_name, property);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct PropertySeed<'a> {
+    deserialize: DeserializePropertyFn,
+    full_name: &'a str,
+    /// Records the property name and the error when the value is invalid.
+    invalid_property: &'a Cell<Option<(String, String)>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PropertySeed<'a> {
+    type Value = Box<dyn ThemeProperty>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.deserialize)(&mut deserializer).map_err(|error| {
+            self.invalid_property
+                .set(Some((self.full_name.to_string(), error.to_string())));
+            de::Error::custom(format!("invalid value for `{}`: {}", self.full_name, error))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_values_are_reported_with_their_property() {
+        let ron = r#"(
+            properties: {
+                "button:flex-grow": 1.0,
+                "button:hover:size": "large",
+            },
+        )"#;
+        let error = load_theme(&ThemePropertyRegistry::default(), ron.as_bytes()).unwrap_err();
+        let (property, _) = error.invalid_property.as_ref().unwrap();
+        assert_eq!(property, "button:hover:size");
+        assert!(error
+            .message
+            .contains("invalid value for `button:hover:size`:"));
+    }
+
+    #[test]
+    fn other_errors_have_no_invalid_property() {
+        let ron = r#"(properties: { "button:unknown": 1.0 })"#;
+        let error = load_theme(&ThemePropertyRegistry::default(), ron.as_bytes()).unwrap_err();
+        assert_eq!(error.invalid_property, None);
+        assert!(error.message.contains("unknown property name `unknown`"));
+    }
+}
@@ -0,0 +1,136 @@
+use bevy_app::prelude::*;
+use bevy_ecs::{prelude::*, system::Command};
+use smallvec::SmallVec;
+
+/// Names of the registered marker components present on an entity.
+///
+/// Selectors can use these names to match widget parts without a [`ThemeKey`](super::ThemeKey),
+/// e.g. `"SliderThumbNode"`. This component is managed automatically for markers registered with
+/// [`ThemeMarkerAppExt::register_theme_marker`].
+#[derive(Component, Default, Debug)]
+pub struct ThemeMarkers(SmallVec<[&'static str; 2]>);
+
+impl ThemeMarkers {
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|marker| *marker == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+pub trait ThemeMarkerAppExt {
+    /// Allows selectors to match entities with the `T` component by using `name`.
+    fn register_theme_marker<T: Component>(&mut self, name: &'static str) -> &mut Self;
+}
+
+impl ThemeMarkerAppExt for App {
+    fn register_theme_marker<T: Component>(&mut self, name: &'static str) -> &mut Self {
+        // Removed markers are found by comparing the names with the components rather than with
+        // `RemovedComponents`, which misses removals made after this system ran in the previous frame.
+        self.add_system_to_stage(
+            CoreStage::First,
+            move |mut commands: Commands,
+                  added: Query<Entity, Added<T>>,
+                  stale: Query<(Entity, &ThemeMarkers), Without<T>>| {
+                for entity in added.iter() {
+                    commands.add(UpdateThemeMarker {
+                        entity,
+                        name,
+                        present: true,
+                    });
+                }
+                for (entity, _) in stale.iter().filter(|(_, markers)| markers.contains(name)) {
+                    commands.add(UpdateThemeMarker {
+                        entity,
+                        name,
+                        present: false,
+                    });
+                }
+            },
+        )
+    }
+}
+
+/// Adds or removes a name from an entity's [`ThemeMarkers`].
+///
+/// This is done with a command so that multiple markers added on the same frame don't overwrite each other.
+struct UpdateThemeMarker {
+    entity: Entity,
+    name: &'static str,
+    present: bool,
+}
+
+impl Command for UpdateThemeMarker {
+    fn write(self, world: &mut World) {
+        let mut entity = match world.get_entity_mut(self.entity) {
+            Some(entity) => entity,
+            None => return,
+        };
+        match entity.get_mut::<ThemeMarkers>() {
+            Some(mut markers) if self.present => {
+                if !markers.contains(self.name) {
+                    markers.0.push(self.name);
+                }
+            }
+            Some(mut markers) => markers.0.retain(|marker| *marker != self.name),
+            None if self.present => {
+                entity.insert(ThemeMarkers(SmallVec::from_slice(&[self.name])));
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::SystemState;
+
+    use super::*;
+    use crate::theming::{Selector, ThemeNodeQuery};
+
+    #[derive(Component)]
+    struct TestMarker;
+
+    /// Whether [`remove_marker`] removes the marker during the next update.
+    struct RemoveMarker(bool);
+
+    fn remove_marker(
+        mut commands: Commands,
+        remove: Res<RemoveMarker>,
+        query: Query<Entity, With<TestMarker>>,
+    ) {
+        if remove.0 {
+            for entity in query.iter() {
+                commands.entity(entity).remove::<TestMarker>();
+            }
+        }
+    }
+
+    fn selector_matches(world: &mut World, selector: &str, entity: Entity) -> bool {
+        let mut state = SystemState::<ThemeNodeQuery>::new(world);
+        let query = state.get(world);
+        Selector::parse(selector).matches(entity, &query)
+    }
+
+    #[test]
+    fn removed_markers_stop_matching() {
+        let mut app = App::new();
+        app.insert_resource(RemoveMarker(false))
+            .register_theme_marker::<TestMarker>("TestMarker")
+            .add_system(remove_marker);
+        let entity = app.world.spawn().insert(TestMarker).id();
+
+        app.update();
+        assert!(selector_matches(&mut app.world, "TestMarker", entity));
+
+        // The marker is removed after the markers were updated, as widgets would do.
+        app.world.resource_mut::<RemoveMarker>().0 = true;
+        app.update();
+        app.update();
+        let markers = app.world.get::<ThemeMarkers>(entity).unwrap();
+        assert!(!markers.contains("TestMarker"));
+        assert!(!selector_matches(&mut app.world, "TestMarker", entity));
+    }
+}
@@ -0,0 +1,485 @@
+use std::borrow::Cow;
+
+use bevy_ecs::{prelude::*, query::WorldQuery, system::SystemParam};
+use bevy_hierarchy::prelude::*;
+use bevy_text::prelude::*;
+use bevy_ui::prelude::*;
+
+use super::{
+    RuleId, TextSectionTags, Theme, ThemeKey, ThemeManager, ThemeMarkers, ThemeProperty,
+    ThemePropertyName,
+};
+use crate::components::{
+    state::{Disabled, Focused},
+    toggle::Toggle,
+};
+
+/// Components used to match selectors against an entity, along with their change trackers.
+#[derive(WorldQuery)]
+pub(crate) struct ThemeNode<'w> {
+    pub entity: Entity,
+    pub key: Option<&'w ThemeKey>,
+    pub markers: Option<&'w ThemeMarkers>,
+    pub parent: Option<&'w Parent>,
+    pub interaction: Option<&'w Interaction>,
+    pub toggle: Option<&'w Toggle>,
+    pub focused: Option<&'w Focused>,
+    pub disabled: Option<&'w Disabled>,
+    key_tracker: Option<ChangeTrackers<ThemeKey>>,
+    markers_tracker: Option<ChangeTrackers<ThemeMarkers>>,
+    parent_tracker: Option<ChangeTrackers<Parent>>,
+    interaction_tracker: Option<ChangeTrackers<Interaction>>,
+    toggle_tracker: Option<ChangeTrackers<Toggle>>,
+    focused_tracker: Option<ChangeTrackers<Focused>>,
+    disabled_tracker: Option<ChangeTrackers<Disabled>>,
+    section_tags_tracker: Option<ChangeTrackers<TextSectionTags>>,
+}
+
+impl<'w> ThemeNodeItem<'w> {
+    fn is_changed(&self) -> bool {
+        fn changed<T: Component>(tracker: &Option<ChangeTrackers<T>>) -> bool {
+            tracker
+                .as_ref()
+                .map_or(false, |tracker| tracker.is_changed())
+        }
+        changed(&self.key_tracker)
+            || changed(&self.markers_tracker)
+            || changed(&self.parent_tracker)
+            || changed(&self.interaction_tracker)
+            || changed(&self.toggle_tracker)
+            || changed(&self.focused_tracker)
+            || changed(&self.disabled_tracker)
+            || changed(&self.section_tags_tracker)
+    }
+}
+
+/// Query used to match selectors against entities and their ancestors.
+pub(crate) type ThemeNodeQuery<'w, 's> = Query<'w, 's, ThemeNode<'static>>;
+
+/// Components of an entity that can be removed and that are used by selectors.
+///
+/// The state is stored with the matched rules and compared whenever the hierarchy is walked, so
+/// that entities whose components were removed are matched again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct MatchState {
+    parent: Option<Entity>,
+    key: bool,
+    markers: bool,
+    focused: bool,
+    disabled: bool,
+    section_tags: bool,
+}
+
+impl MatchState {
+    fn new(node: &ThemeNodeItem) -> Self {
+        Self {
+            parent: node.parent.map(|parent| parent.get()),
+            key: node.key.is_some(),
+            markers: node.markers.is_some(),
+            focused: node.focused.is_some(),
+            disabled: node.disabled.is_some(),
+            section_tags: node.section_tags_tracker.is_some(),
+        }
+    }
+}
+
+/// Whether components used by selectors were removed since [`update_theme_cache`] last ran.
+///
+/// [`RemovedComponents`] only reports removals made during the current frame, so removals made
+/// after the rules were matched are recorded at the end of the frame by [`record_theme_removals`].
+#[derive(Default)]
+pub(crate) struct ThemeRemovals(bool);
+
+/// Records the removals of components used by selectors into [`ThemeRemovals`].
+///
+/// This runs before [`update_theme_cache`] to catch removals made earlier in the frame, and
+/// exclusively at the end of [`CoreStage::Last`](bevy_app::CoreStage::Last) to catch the others
+/// before they are cleared.
+pub(crate) fn record_theme_removals(
+    mut removals: ResMut<ThemeRemovals>,
+    keys: RemovedComponents<ThemeKey>,
+    markers: RemovedComponents<ThemeMarkers>,
+    parents: RemovedComponents<Parent>,
+    focused: RemovedComponents<Focused>,
+    disabled: RemovedComponents<Disabled>,
+    section_tags: RemovedComponents<TextSectionTags>,
+) {
+    if keys.iter().next().is_some()
+        || markers.iter().next().is_some()
+        || parents.iter().next().is_some()
+        || focused.iter().next().is_some()
+        || disabled.iter().next().is_some()
+        || section_tags.iter().next().is_some()
+    {
+        removals.0 = true;
+    }
+}
+
+/// Entities whose components used by selectors were added or changed.
+type ChangedNodes = Or<(
+    Changed<ThemeKey>,
+    Changed<ThemeMarkers>,
+    Changed<Parent>,
+    Changed<Interaction>,
+    Changed<Toggle>,
+    Changed<Focused>,
+    Changed<Disabled>,
+    Changed<TextSectionTags>,
+    Added<Node>,
+    Added<Text>,
+)>;
+
+/// Entities that can be matched by selectors, along with their children.
+#[derive(SystemParam)]
+pub(crate) struct ThemeHierarchy<'w, 's> {
+    query: ThemeNodeQuery<'w, 's>,
+    children: Query<'w, 's, &'static Children>,
+}
+
+impl<'w, 's> ThemeHierarchy<'w, 's> {
+    fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.query
+            .get(entity)
+            .ok()
+            .and_then(|node| node.parent.map(|parent| parent.get()))
+    }
+}
+
+/// Rules of the active theme matched by an entity, by decreasing precedence.
+///
+/// This is kept up to date by [`update_theme_cache`] for UI nodes, texts and entities with a
+/// [`ThemeKey`] or [`ThemeMarkers`], so that resolving their properties doesn't require matching
+/// every selector again. Properties are looked up by [`RuleId`] and type, without hashing strings
+/// or allocating.
+#[derive(Component, Default, PartialEq)]
+pub(crate) struct ThemeCache {
+    /// State of the entity when it was last matched, or `None` if it wasn't matched yet.
+    state: Option<MatchState>,
+    rules: Vec<RuleId>,
+    sections: Vec<RuleId>,
+    /// The entity's rules followed by the rules of its ancestors, from the nearest to the farthest.
+    inherited: Vec<RuleId>,
+}
+
+impl ThemeCache {
+    fn new(
+        entity: Entity,
+        theme: &ThemeManager,
+        query: &ThemeNodeQuery,
+        state: MatchState,
+        ancestors: &[RuleId],
+    ) -> Self {
+        let rules = theme.matching_rules(entity, query);
+        let inherited = rules.iter().chain(ancestors).copied().collect();
+        Self {
+            state: Some(state),
+            rules,
+            sections: theme.matching_section_rules(entity, query),
+            inherited,
+        }
+    }
+}
+
+/// Entities that keep a [`ThemeCache`].
+type Cached = Or<(With<Node>, With<Text>, With<ThemeKey>, With<ThemeMarkers>)>;
+
+/// Adds an empty [`ThemeCache`] to the entities that can be themed, and removes it from the others.
+///
+/// This runs exclusively at the start of [`CoreStage::PreUpdate`](bevy_app::CoreStage::PreUpdate),
+/// so that entities spawned during the previous frame are matched by [`update_theme_cache`] and
+/// styled before they are first rendered.
+pub(crate) fn insert_theme_caches(world: &mut World) {
+    let mut missing = world.query_filtered::<Entity, (Cached, Without<ThemeCache>)>();
+    let missing: Vec<_> = missing.iter(world).collect();
+    for entity in missing {
+        world.entity_mut(entity).insert(ThemeCache::default());
+    }
+    let mut stale = world.query_filtered::<Entity, (
+        With<ThemeCache>,
+        Without<Node>,
+        Without<Text>,
+        Without<ThemeKey>,
+        Without<ThemeMarkers>,
+    )>();
+    let stale: Vec<_> = stale.iter(world).collect();
+    for entity in stale {
+        world.entity_mut(entity).remove::<ThemeCache>();
+    }
+}
+
+/// Matches entities against the rules of the active theme when they or their ancestors change, or
+/// when rules are added to the theme.
+///
+/// The hierarchy is walked from the roots so that changes of an entity are propagated to its
+/// descendants, whose descendant selectors and inherited rules may change. Reparenting a subtree
+/// changes the parent of its root, so the whole subtree is matched again. The walk is skipped when
+/// no entity changed, no component was removed and the rules didn't change.
+pub(crate) fn update_theme_cache(
+    theme: Option<Res<ThemeManager>>,
+    hierarchy: ThemeHierarchy,
+    mut caches: Query<&mut ThemeCache>,
+    changed_nodes: Query<(), ChangedNodes>,
+    mut removals: ResMut<ThemeRemovals>,
+    mut rules_version: Local<Option<(u64, u64)>>,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    let version = theme.rules_version();
+    let rules_changed = *rules_version != Some(version);
+    *rules_version = Some(version);
+    if !rules_changed && !removals.0 && changed_nodes.is_empty() {
+        return;
+    }
+    removals.0 = false;
+
+    // Entities to visit, along with whether one of their ancestors changed and their nearest
+    // ancestor with a cache, whose inherited rules follow their own.
+    let mut stack: Vec<_> = hierarchy
+        .query
+        .iter()
+        .filter(|node| node.parent.is_none())
+        .map(|root| (root.entity, false, None))
+        .collect();
+    while let Some((entity, ancestor_changed, cached_ancestor)) = stack.pop() {
+        let node = match hierarchy.query.get(entity) {
+            Ok(node) => node,
+            Err(_) => continue,
+        };
+        let state = MatchState::new(&node);
+        let mut changed = ancestor_changed || node.is_changed();
+        let cached_state = caches.get(entity).ok().map(|cache| cache.state);
+        if let Some(cached_state) = cached_state {
+            changed |= cached_state != Some(state);
+            if changed || rules_changed {
+                let ancestors = cached_ancestor
+                    .and_then(|ancestor| caches.get(ancestor).ok())
+                    .map(|cache| cache.inherited.clone())
+                    .unwrap_or_default();
+                let matched = ThemeCache::new(entity, &theme, &hierarchy.query, state, &ancestors);
+                if let Ok(mut cache) = caches.get_mut(entity) {
+                    if *cache != matched {
+                        *cache = matched;
+                    }
+                }
+            }
+        }
+        let cached_ancestor = if cached_state.is_some() {
+            Some(entity)
+        } else {
+            cached_ancestor
+        };
+        if let Ok(children) = hierarchy.children.get(entity) {
+            stack.extend(
+                children
+                    .iter()
+                    .map(|child| (*child, changed, cached_ancestor)),
+            );
+        }
+    }
+}
+
+/// Entities that can be matched by selectors, along with their cached matched rules.
+#[derive(SystemParam)]
+pub(crate) struct ThemeNodes<'w, 's> {
+    hierarchy: ThemeHierarchy<'w, 's>,
+    caches: Query<'w, 's, (&'static ThemeCache, ChangeTrackers<ThemeCache>)>,
+    changed_caches: Query<'w, 's, (), Changed<ThemeCache>>,
+}
+
+/// Rules matching an entity, used to resolve its properties.
+///
+/// Rules are ordered by precedence: the entity's own rules come first, from the most specific to
+/// the least specific. When resolving inherited properties, they are followed by the rules of its
+/// ancestors from the nearest to the farthest. A property is taken from the first rule that sets it.
+///
+/// Rules targeting the entity's text sections are kept aside and only used by
+/// [`MatchedSelectors::for_section`].
+pub(crate) struct MatchedSelectors<'a> {
+    theme: &'a Theme,
+    rules: Cow<'a, [RuleId]>,
+    sections: Cow<'a, [RuleId]>,
+    /// Index and tag of the text section whose properties are resolved, if any.
+    section: Option<(usize, Option<&'a str>)>,
+}
+
+impl<'a> MatchedSelectors<'a> {
+    pub(crate) fn get_property<T>(&self) -> Option<&'a T>
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        let section_rules = self.section.into_iter().flat_map(|(index, tag)| {
+            self.sections.iter().filter(move |rule| {
+                self.theme
+                    .rule_selector(**rule)
+                    .section()
+                    .map_or(false, |section| section.matches(index, tag))
+            })
+        });
+        section_rules
+            .chain(self.rules.iter())
+            .find_map(|rule| self.theme.rule_property::<T>(*rule))
+    }
+
+    /// Returns the rules used to resolve the properties of a text section, in which rules
+    /// targeting the section come before the others.
+    pub(crate) fn for_section<'s>(
+        &'s self,
+        index: usize,
+        tag: Option<&'s str>,
+    ) -> MatchedSelectors<'s> {
+        MatchedSelectors {
+            theme: self.theme,
+            rules: Cow::Borrowed(&self.rules),
+            sections: Cow::Borrowed(&self.sections),
+            section: Some((index, tag)),
+        }
+    }
+}
+
+impl<'w, 's> ThemeNodes<'w, 's> {
+    /// Returns `true` if no entity matched other rules and no property changed since properties
+    /// were last applied, in which case only the entities that are forced to update need to be
+    /// visited.
+    pub(crate) fn is_unchanged(&self, theme: &ThemeManager) -> bool {
+        !theme.has_changed_selectors() && self.changed_caches.is_empty()
+    }
+
+    /// Returns the rules of the active theme matching the entity, including the rules of its
+    /// ancestors when `inherit` is `true`.
+    ///
+    /// Returns `None` when neither the matched rules nor their properties changed since properties
+    /// were last applied, in which case the entity doesn't need to be updated. Entities are always
+    /// updated when `force` is `true`, e.g. while they are animating.
+    pub(crate) fn match_selectors<'a>(
+        &'a self,
+        entity: Entity,
+        theme: &'a ThemeManager,
+        inherit: bool,
+        force: bool,
+    ) -> Option<MatchedSelectors<'a>> {
+        let (rules, sections, changed) = match self.caches.get(entity) {
+            Ok((cache, tracker)) => {
+                if !force && !tracker.is_changed() && !theme.has_changed_selectors() {
+                    return None;
+                }
+                let rules = if inherit {
+                    &cache.inherited
+                } else {
+                    &cache.rules
+                };
+                let changed = force || tracker.is_changed();
+                (
+                    Cow::Borrowed(&rules[..]),
+                    Cow::Borrowed(&cache.sections[..]),
+                    changed,
+                )
+            }
+            // Entities spawned during this stage don't have a cache yet, and are matched every time
+            // they are updated.
+            Err(_) => {
+                let mut rules = theme.matching_rules(entity, &self.hierarchy.query);
+                if inherit {
+                    let mut parent = self.hierarchy.parent(entity);
+                    while let Some(ancestor) = parent {
+                        // The nearest cached ancestor already has the rules of the farther ones.
+                        if let Ok((cache, _)) = self.caches.get(ancestor) {
+                            rules.extend_from_slice(&cache.inherited);
+                            break;
+                        }
+                        rules.extend(theme.matching_rules(ancestor, &self.hierarchy.query));
+                        parent = self.hierarchy.parent(ancestor);
+                    }
+                }
+                let sections = theme.matching_section_rules(entity, &self.hierarchy.query);
+                (Cow::Owned(rules), Cow::Owned(sections), true)
+            }
+        };
+        let changed = changed
+            || rules
+                .iter()
+                .chain(sections.iter())
+                .any(|rule| theme.is_rule_changed(*rule));
+        if changed {
+            Some(MatchedSelectors {
+                theme: theme.active_theme(),
+                rules,
+                sections,
+                section: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::prelude::*;
+    use bevy_render::prelude::*;
+
+    use super::*;
+    use crate::theming::{ColorProperty, ThemingSystem};
+
+    /// Whether [`remove_focus`] removes [`Focused`] during the next update.
+    struct RemoveFocus(bool);
+
+    fn remove_focus(
+        mut commands: Commands,
+        remove: Res<RemoveFocus>,
+        query: Query<Entity, With<Focused>>,
+    ) {
+        if remove.0 {
+            for entity in query.iter() {
+                commands.entity(entity).remove::<Focused>();
+            }
+        }
+    }
+
+    fn matched_rules(app: &App, entity: Entity) -> usize {
+        app.world.get::<ThemeCache>(entity).unwrap().rules.len()
+    }
+
+    #[test]
+    fn removed_components_are_matched_again() {
+        let mut theme = ThemeManager::new();
+        theme.set_property("button:focused", ColorProperty(Color::WHITE));
+        let mut app = App::new();
+        app.insert_resource(theme)
+            .insert_resource(RemoveFocus(false))
+            .init_resource::<ThemeRemovals>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                insert_theme_caches.exclusive_system().at_start(),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                record_theme_removals.before(ThemingSystem::Match),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_theme_cache.label(ThemingSystem::Match),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                record_theme_removals.exclusive_system().at_end(),
+            )
+            .add_system(remove_focus);
+        let entity = app
+            .world
+            .spawn()
+            .insert(ThemeKey::from("button"))
+            .insert(Focused)
+            .id();
+
+        app.update();
+        assert_eq!(matched_rules(&app, entity), 1);
+
+        // `Focused` is removed during `Update`, after the caches were updated.
+        app.world.resource_mut::<RemoveFocus>().0 = true;
+        app.update();
+        app.update();
+        assert_eq!(matched_rules(&app, entity), 0);
+    }
+}
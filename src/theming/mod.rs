@@ -1,68 +1,300 @@
+mod binding;
+mod builtin;
+mod decoration;
+mod diagnostics;
+mod export;
+mod loader;
+mod marker;
+mod matching;
 mod properties;
+mod selector;
+mod theme;
 mod theme_manager;
+mod transition;
+mod variable;
 
 use bevy_app::prelude::*;
 use bevy_asset::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_text::prelude::*;
-use bevy_ui::prelude::*;
+use bevy_time::prelude::*;
+use bevy_ui::{prelude::*, UiSystem};
+use smallvec::SmallVec;
 
+pub use self::binding::*;
+pub use self::builtin::*;
+pub use self::decoration::*;
+pub use self::diagnostics::*;
+pub use self::export::*;
+pub use self::loader::*;
+pub use self::marker::*;
+pub(crate) use self::matching::*;
 pub use self::properties::*;
+pub use self::selector::*;
+pub use self::theme::*;
 pub use self::theme_manager::*;
+pub use self::transition::*;
+pub use self::variable::*;
 
+/// Applies the properties of the [`ThemeManager`]'s active theme to the entities matched by their [`Selector`].
+///
+/// Properties are applied to entities when their [`ThemeKey`], [`ThemeMarkers`], interaction state or
+/// position in the hierarchy changes, and to every matched entity when properties of a selector change. Switching
+/// the active theme reapplies all properties to all matched entities.
+///
+/// Built-in properties are applied to [`Style`], [`UiColor`], [`UiImage`] and [`Text`], and borders are drawn with
+/// child nodes tracked by [`ThemeDecorations`]. Other properties and components can be
+/// themed with [`ThemePropertyAppExt`].
+///
+/// The plugin keeps a [`ThemeManager`] inserted before it, and otherwise starts with an empty default theme,
+/// so widgets keep the colours of their builders. The [built-in theme](Theme::builtin) is opted in with
+/// [`ThemeManager::with_builtin`], in which case it overrides those colours.
 pub struct ThemingPlugin;
 
-// TODO: properly handle Added and Changed for the key component.
-// TODO: hierarchy traversal for inheritable properties (e.g. text color, font, size)
-
 // Plugin that enables the systems for the theming module
 impl Plugin for ThemingPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(ThemeManager::new())
+        app.init_resource::<ThemeManager>()
+            .add_asset::<Theme>()
+            .init_resource::<ThemePropertyRegistry>()
+            .init_resource::<ThemeLoadErrors>()
+            .init_resource::<ThemeRemovals>()
+            .init_resource::<RunningTransitions>()
+            .init_asset_loader::<ThemeLoader>()
+            .add_system_to_stage(CoreStage::First, update_theme_sources)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                insert_theme_caches.exclusive_system().at_start(),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                record_theme_removals.before(ThemingSystem::Match),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_theme_cache
+                    .label(ThemingSystem::Match)
+                    .after(UiSystem::Focus),
+            )
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
                 SystemSet::new()
+                    .label(ThemingSystem::Apply)
+                    .after(ThemingSystem::Match)
                     .with_system(update_color)
                     .with_system(update_style)
-                    .with_system(update_text_nodes),
+                    .with_system(update_text_nodes)
+                    .with_system(update_image)
+                    .with_system(update_decorations),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                clear_changed_selectors.after(ThemingSystem::Apply),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                clear_stale_transitions.after(ThemingSystem::Apply),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                record_theme_removals.exclusive_system().at_end(),
             );
     }
 }
 
-#[derive(Component)]
-pub struct ThemeKey(pub String);
+/// Labels for the theming systems.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum ThemingSystem {
+    /// Matches entities against the selectors of the active theme when they change.
+    Match,
+    /// Applies theme properties to matched entities.
+    Apply,
+}
+
+/// Classes and optional id used by selectors to match an entity.
+///
+/// Converting from a string splits it into classes, e.g. `ThemeKey::from("button primary")`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct ThemeKey {
+    pub id: Option<String>,
+    pub classes: SmallVec<[String; 2]>,
+}
+
+impl ThemeKey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+}
 
 impl From<&str> for ThemeKey {
     fn from(s: &str) -> Self {
-        Self(s.into())
+        Self {
+            id: None,
+            classes: s.split_whitespace().map(String::from).collect(),
+        }
+    }
+}
+
+/// Tags of the sections of a [`Text`], used by [`SectionSelector::Tag`] to target sections by name.
+///
+/// e.g. `TextSectionTags::default().with_tag(0, "label").with_tag(1, "value")`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TextSectionTags(pub SmallVec<[Option<String>; 2]>);
+
+impl TextSectionTags {
+    pub fn with_tag(mut self, index: usize, tag: impl Into<String>) -> Self {
+        if self.0.len() <= index {
+            self.0.resize(index + 1, None);
+        }
+        self.0[index] = Some(tag.into());
+        self
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.0.get(index).and_then(|tag| tag.as_deref())
     }
 }
 
-fn update_color(theme: Option<Res<ThemeManager>>, mut query: Query<(&ThemeKey, &mut UiColor)>) {
-    if let Some(theme) = theme.filter(|style| style.is_added() || style.is_changed()) {
-        for (key, mut value) in query.iter_mut() {
-            if let Some(property) = theme
-                .get_property::<ColorProperty>(&key.0)
-                .filter(|color| color.0 != value.0)
-            {
-                value.0 = property.0;
+/// Copies properties from the [`ThemeManager`]'s source assets when they are loaded or modified.
+fn update_theme_sources(
+    mut events: EventReader<AssetEvent<Theme>>,
+    assets: Res<Assets<Theme>>,
+    mut theme: ResMut<ThemeManager>,
+) {
+    let modified: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                Some(handle.clone())
             }
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+    for (name, handle) in theme.sources_to_update(&modified) {
+        if let Some(asset) = assets.get(&handle) {
+            theme.apply_source(&name, asset);
         }
     }
 }
 
-fn update_style(theme: Option<Res<ThemeManager>>, mut query: Query<(&ThemeKey, &mut Style)>) {
-    if let Some(theme) = theme.filter(|style| style.is_added() || style.is_changed()) {
-        for (key, mut style) in query.iter_mut() {
+fn clear_changed_selectors(theme: Option<ResMut<ThemeManager>>) {
+    if let Some(mut theme) = theme.filter(|theme| theme.has_changed_selectors()) {
+        theme.clear_changed_selectors();
+    }
+}
+
+fn update_color(
+    mut commands: Commands,
+    theme: Option<Res<ThemeManager>>,
+    time: Res<Time>,
+    running: Res<RunningTransitions>,
+    nodes: ThemeNodes,
+    mut query: Query<
+        (Entity, &mut UiColor, Option<&mut ThemeTransitions>),
+        Or<(With<ThemeKey>, With<ThemeMarkers>)>,
+    >,
+) {
+    if let Some(theme) = theme.filter(|theme| !nodes.is_unchanged(theme) || running.0) {
+        for (entity, mut value, transitions) in query.iter_mut() {
+            let animating = transitions.as_ref().map_or(false, |t| t.is_animating());
+            let selectors = match nodes.match_selectors(entity, &theme, false, animating) {
+                Some(selectors) => selectors,
+                None => continue,
+            };
+            let mut animator = TransitionAnimator::new(
+                transitions,
+                selectors.get_property::<TransitionProperty>(),
+                time.seconds_since_startup(),
+            );
+            if let Some(property) = selectors.get_property::<ColorProperty>() {
+                let color = animator.animate::<ColorProperty, _>(value.0, property.0);
+                if color != value.0 {
+                    value.0 = color;
+                }
+            }
+            animator.finish(entity, &mut commands);
+        }
+    }
+}
+
+fn update_image(
+    theme: Option<Res<ThemeManager>>,
+    asset_server: Res<AssetServer>,
+    nodes: ThemeNodes,
+    mut query: Query<(Entity, &mut UiImage), Or<(With<ThemeKey>, With<ThemeMarkers>)>>,
+) {
+    if let Some(theme) = theme.filter(|theme| !nodes.is_unchanged(theme)) {
+        for (entity, mut image) in query.iter_mut() {
+            let selectors = match nodes.match_selectors(entity, &theme, false, false) {
+                Some(selectors) => selectors,
+                None => continue,
+            };
+            if let Some(property) = selectors.get_property::<BackgroundImageProperty>() {
+                let image_path = asset_server.get_handle_path(image.0.clone());
+                if image_path.map_or(true, |path| path.path().as_os_str() != property.0.as_str()) {
+                    image.0 = asset_server.load(&property.0);
+                }
+            }
+        }
+    }
+}
+
+fn update_style(
+    mut commands: Commands,
+    theme: Option<Res<ThemeManager>>,
+    time: Res<Time>,
+    running: Res<RunningTransitions>,
+    nodes: ThemeNodes,
+    mut query: Query<
+        (Entity, &mut Style, Option<&mut ThemeTransitions>),
+        Or<(With<ThemeKey>, With<ThemeMarkers>)>,
+    >,
+) {
+    if let Some(theme) = theme.filter(|theme| !nodes.is_unchanged(theme) || running.0) {
+        for (entity, mut style, transitions) in query.iter_mut() {
+            let animating = transitions.as_ref().map_or(false, |t| t.is_animating());
+            let selectors = match nodes.match_selectors(entity, &theme, false, animating) {
+                Some(selectors) => selectors,
+                None => continue,
+            };
+            let mut animator = TransitionAnimator::new(
+                transitions,
+                selectors.get_property::<TransitionProperty>(),
+                time.seconds_since_startup(),
+            );
             macro_rules! change_style {
                 ($a: ty, $b: expr) => {
-                    if let Some(property) = theme.get_property::<$a>(&key.0) {
+                    if let Some(property) = selectors.get_property::<$a>() {
                         if $b != property.0 {
                             $b = property.0;
                         }
                     }
                 };
             }
+            macro_rules! animate_style {
+                ($a: ty, $b: expr) => {
+                    if let Some(property) = selectors.get_property::<$a>() {
+                        let value = animator.animate::<$a, _>($b, property.0);
+                        if $b != value {
+                            $b = value;
+                        }
+                    }
+                };
+            }
 
             change_style!(DisplayProperty, style.display);
             change_style!(PositionTypeProperty, style.position_type);
@@ -73,60 +305,106 @@ fn update_style(theme: Option<Res<ThemeManager>>, mut query: Query<(&ThemeKey, &
             change_style!(AlignSelfProperty, style.align_self);
             change_style!(AlignContentProperty, style.align_content);
             change_style!(JustifyContentProperty, style.justify_content);
-            change_style!(PositionProperty, style.position);
-            change_style!(MarginProperty, style.margin);
-            change_style!(PaddingProperty, style.padding);
-            change_style!(BorderProperty, style.border);
-            change_style!(FlexGrowProperty, style.flex_grow);
-            change_style!(FlexShrinkProperty, style.flex_shrink);
-            change_style!(FlexBasisProperty, style.flex_basis);
-            change_style!(SizeProperty, style.size);
-            change_style!(MinSizeProperty, style.min_size);
-            change_style!(MaxSizeProperty, style.max_size);
+            animate_style!(PositionProperty, style.position);
+            animate_style!(MarginProperty, style.margin);
+            animate_style!(PaddingProperty, style.padding);
+            animate_style!(BorderProperty, style.border);
+            animate_style!(FlexGrowProperty, style.flex_grow);
+            animate_style!(FlexShrinkProperty, style.flex_shrink);
+            animate_style!(FlexBasisProperty, style.flex_basis);
+            animate_style!(SizeProperty, style.size);
+            animate_style!(MinSizeProperty, style.min_size);
+            animate_style!(MaxSizeProperty, style.max_size);
             change_style!(AspectRatioProperty, style.aspect_ratio);
             change_style!(OverflowProperty, style.overflow);
+            animator.finish(entity, &mut commands);
         }
     }
 }
 
-// TODO: support more properties for text nodes
-// TODO: support having distinct properties for text sections
+/// Applies text properties to text nodes.
+///
+/// Text properties are inheritable: when they are not set by the selectors matching the node, they
+/// are taken from the nearest ancestor matched by a selector that sets them. Text nodes don't need
+/// a [`ThemeKey`] of their own to inherit properties.
 fn update_text_nodes(
+    mut commands: Commands,
     theme: Option<Res<ThemeManager>>,
+    time: Res<Time>,
     asset_server: Res<AssetServer>,
-    mut query: Query<(&ThemeKey, &mut Text)>,
+    running: Res<RunningTransitions>,
+    nodes: ThemeNodes,
+    mut query: Query<(
+        Entity,
+        &mut Text,
+        Option<&TextSectionTags>,
+        Option<ChangeTrackers<TextSectionTags>>,
+        Option<&mut ThemeTransitions>,
+    )>,
+    changed_tags: Query<(), (With<Text>, Changed<TextSectionTags>)>,
 ) {
-    if let Some(theme) = theme.filter(|style| style.is_added() || style.is_changed()) {
-        for (key, mut text) in query.iter_mut() {
-            if let Some(property) = theme.get_property::<TextHorizontalAlignProperty>(&key.0) {
-                if text.alignment.horizontal != property.0 {
-                    text.alignment.horizontal = property.0;
-                }
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    if nodes.is_unchanged(&theme) && !running.0 && changed_tags.is_empty() {
+        return;
+    }
+    for (entity, mut text, tags, tags_tracker, transitions) in query.iter_mut() {
+        let animating = transitions.as_ref().map_or(false, |t| t.is_animating());
+        // Tags select section rules without changing the rules matched by the entity.
+        let tags_changed = tags_tracker.map_or(false, |tracker| tracker.is_changed());
+        let force = animating || tags_changed;
+        let selectors = match nodes.match_selectors(entity, &theme, true, force) {
+            Some(selectors) => selectors,
+            None => continue,
+        };
+        let mut animator = TransitionAnimator::new(
+            transitions,
+            selectors.get_property::<TransitionProperty>(),
+            time.seconds_since_startup(),
+        );
+        if let Some(property) = selectors.get_property::<TextHorizontalAlignProperty>() {
+            if text.alignment.horizontal != property.0 {
+                text.alignment.horizontal = property.0;
             }
-            if let Some(property) = theme.get_property::<TextVerticalAlignProperty>(&key.0) {
-                if text.alignment.vertical != property.0 {
-                    text.alignment.vertical = property.0;
-                }
+        }
+        if let Some(property) = selectors.get_property::<TextVerticalAlignProperty>() {
+            if text.alignment.vertical != property.0 {
+                text.alignment.vertical = property.0;
             }
-            if let Some(property) = theme.get_property::<TextColorProperty>(&key.0) {
-                text.sections.iter_mut().for_each(|section| {
-                    section.style.color = property.0;
-                })
+        }
+        // Sections are themed separately so that selectors like `"label::1"` can target some of them.
+        for (index, section) in text.sections.iter_mut().enumerate() {
+            let tag = tags.and_then(|tags| tags.get(index));
+            let selectors = selectors.for_section(index, tag);
+            if let Some(property) = selectors.get_property::<TextColorProperty>() {
+                let color = animator.animate_part::<TextColorProperty, _>(
+                    index,
+                    section.style.color,
+                    property.0,
+                );
+                if color != section.style.color {
+                    section.style.color = color;
+                }
             }
-            if let Some(property) = theme.get_property::<TextFontSizeProperty>(&key.0) {
-                text.sections.iter_mut().for_each(|section| {
-                    section.style.font_size = property.0;
-                })
+            if let Some(property) = selectors.get_property::<TextFontSizeProperty>() {
+                let font_size = animator.animate_part::<TextFontSizeProperty, _>(
+                    index,
+                    section.style.font_size,
+                    property.0,
+                );
+                if font_size != section.style.font_size {
+                    section.style.font_size = font_size;
+                }
             }
-            if let Some(property) = theme.get_property::<TextFontPathProperty>(&key.0) {
-                text.sections.iter_mut().for_each(|section| {
-                    let font_path = asset_server
-                        .get_handle_path(section.style.font.clone());
-                    if font_path.map_or(true, |path| path.path().as_os_str() != property.0.as_str()) {
-                        section.style.font = asset_server.load(&property.0);
-                    }
-                })
+            if let Some(property) = selectors.get_property::<TextFontPathProperty>() {
+                let font_path = asset_server.get_handle_path(section.style.font.clone());
+                if font_path.map_or(true, |path| path.path().as_os_str() != property.0.as_str()) {
+                    section.style.font = asset_server.load(&property.0);
+                }
             }
         }
+        animator.finish(entity, &mut commands);
     }
 }
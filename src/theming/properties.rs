@@ -1,8 +1,11 @@
-use std::any::Any;
+use std::{any::Any, collections::BTreeMap};
 
 use bevy_render::prelude::*;
 use bevy_text::prelude::*;
 use bevy_ui::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{ThemePropertyRegistry, ThemeVariable, Transition};
 
 pub trait ThemePropertyName
 where
@@ -11,6 +14,11 @@ where
     /// The unique name for this property.
     /// Used for internal storage and serialization.
     const PROPERTY_NAME: &'static str;
+
+    /// Creates the property from the value of a variable, if it has a compatible type.
+    fn from_variable(_variable: &ThemeVariable) -> Option<Self> {
+        None
+    }
 }
 
 pub trait ThemeProperty
@@ -18,19 +26,71 @@ where
     Self: Any + Send + Sync,
 {
     fn as_any(&self) -> &dyn Any;
+
+    /// Returns the [`ThemePropertyName::PROPERTY_NAME`] of the property.
+    fn name(&self) -> &'static str;
+
+    /// Clones the property into a new box.
+    fn box_clone(&self) -> Box<dyn ThemeProperty>;
+
+    /// Returns the property as a serializable value, used to export themes.
+    fn as_serialize(&self) -> &dyn erased_serde::Serialize;
 }
 
-macro_rules! define_property {
+// Used by `define_theme_property!`, so that crates using it don't need to depend on `erased_serde`.
+#[doc(hidden)]
+pub use erased_serde;
+
+/// Defines a theme property wrapping a value of the given type, with the given property name.
+///
+/// The inner type must implement `Debug`, `Clone` and serde traits, and the crate using this macro
+/// must depend on `serde`. Register the property with
+/// [`ThemePropertyAppExt`](crate::theming::ThemePropertyAppExt) to load it from theme files and
+/// apply it to components:
+///
+/// ```ignore
+/// define_theme_property!(TooltipOffsetProperty, f32, "tooltip-offset");
+/// ```
+#[macro_export]
+macro_rules! define_theme_property {
     ($type: ident, $inner_type: ty, $name_str: expr) => {
-        #[derive(Debug, Clone)]
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        #[serde(transparent)]
         pub struct $type(pub $inner_type);
 
-        impl ThemePropertyName for $type {
+        $crate::define_theme_property!(@impl $type, $inner_type, $name_str);
+    };
+    // Used for inner types that don't implement serde traits, with a module implementing them.
+    ($type: ident, $inner_type: ty, $name_str: expr, $serde_with: literal) => {
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        #[serde(transparent)]
+        pub struct $type(#[serde(with = $serde_with)] pub $inner_type);
+
+        $crate::define_theme_property!(@impl $type, $inner_type, $name_str);
+    };
+    (@impl $type: ident, $inner_type: ty, $name_str: expr) => {
+        impl $crate::theming::ThemePropertyName for $type {
             const PROPERTY_NAME: &'static str = $name_str;
+
+            fn from_variable(variable: &$crate::theming::ThemeVariable) -> Option<Self> {
+                variable.convert::<$inner_type>().map(Self)
+            }
         }
 
-        impl ThemeProperty for $type {
-            fn as_any(&self) -> &dyn Any {
+        impl $crate::theming::ThemeProperty for $type {
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn name(&self) -> &'static str {
+                <Self as $crate::theming::ThemePropertyName>::PROPERTY_NAME
+            }
+
+            fn box_clone(&self) -> Box<dyn $crate::theming::ThemeProperty> {
+                Box::new(self.clone())
+            }
+
+            fn as_serialize(&self) -> &dyn $crate::theming::erased_serde::Serialize {
                 self
             }
         }
@@ -38,34 +98,191 @@ macro_rules! define_property {
 }
 
 // Define Style properties
-define_property!(DisplayProperty, Display, "display");
-define_property!(PositionTypeProperty, PositionType, "position-type");
-define_property!(DirectionProperty, Direction, "direction");
-define_property!(FlexDirectionProperty, FlexDirection, "flex-direction");
-define_property!(FlexWrapProperty, FlexWrap, "flex-wrap");
-define_property!(AlignItemsProperty, AlignItems, "align-items");
-define_property!(AlignSelfProperty, AlignSelf, "align-self");
-define_property!(AlignContentProperty, AlignContent, "align-content");
-define_property!(JustifyContentProperty, JustifyContent, "justify-content");
-define_property!(PositionProperty, UiRect<Val>, "position");
-define_property!(MarginProperty, UiRect<Val>, "margin");
-define_property!(PaddingProperty, UiRect<Val>, "padding");
-define_property!(BorderProperty, UiRect<Val>, "border");
-define_property!(FlexGrowProperty, f32, "flex-grow");
-define_property!(FlexShrinkProperty, f32, "flex-shrink");
-define_property!(FlexBasisProperty, Val, "flex-basis");
-define_property!(SizeProperty, Size<Val>, "size");
-define_property!(MinSizeProperty, Size<Val>, "min-size");
-define_property!(MaxSizeProperty, Size<Val>, "max-size");
-define_property!(AspectRatioProperty, Option<f32>, "aspect-ratio");
-define_property!(OverflowProperty, Overflow, "overflow");
+define_theme_property!(DisplayProperty, Display, "display");
+define_theme_property!(PositionTypeProperty, PositionType, "position-type");
+define_theme_property!(DirectionProperty, Direction, "direction");
+define_theme_property!(FlexDirectionProperty, FlexDirection, "flex-direction");
+define_theme_property!(FlexWrapProperty, FlexWrap, "flex-wrap");
+define_theme_property!(AlignItemsProperty, AlignItems, "align-items");
+define_theme_property!(AlignSelfProperty, AlignSelf, "align-self");
+define_theme_property!(AlignContentProperty, AlignContent, "align-content");
+define_theme_property!(JustifyContentProperty, JustifyContent, "justify-content");
+define_theme_property!(PositionProperty, UiRect<Val>, "position", "ui_rect_serde");
+define_theme_property!(MarginProperty, UiRect<Val>, "margin", "ui_rect_serde");
+define_theme_property!(PaddingProperty, UiRect<Val>, "padding", "ui_rect_serde");
+define_theme_property!(BorderProperty, UiRect<Val>, "border", "ui_rect_serde");
+define_theme_property!(FlexGrowProperty, f32, "flex-grow");
+define_theme_property!(FlexShrinkProperty, f32, "flex-shrink");
+define_theme_property!(FlexBasisProperty, Val, "flex-basis");
+define_theme_property!(SizeProperty, Size<Val>, "size", "size_serde");
+define_theme_property!(MinSizeProperty, Size<Val>, "min-size", "size_serde");
+define_theme_property!(MaxSizeProperty, Size<Val>, "max-size", "size_serde");
+define_theme_property!(AspectRatioProperty, Option<f32>, "aspect-ratio");
+define_theme_property!(OverflowProperty, Overflow, "overflow");
 
 // Define text properties
-define_property!(TextColorProperty, Color, "color");
-define_property!(TextFontPathProperty, String, "font-path");
-define_property!(TextFontSizeProperty, f32, "font-size");
-define_property!(TextHorizontalAlignProperty, HorizontalAlign, "text-horizontal-align");
-define_property!(TextVerticalAlignProperty, VerticalAlign, "text-vertical-align");
+define_theme_property!(TextColorProperty, Color, "color");
+define_theme_property!(TextFontPathProperty, String, "font-path");
+define_theme_property!(TextFontSizeProperty, f32, "font-size");
+define_theme_property!(
+    TextHorizontalAlignProperty,
+    HorizontalAlign,
+    "text-horizontal-align"
+);
+define_theme_property!(
+    TextVerticalAlignProperty,
+    VerticalAlign,
+    "text-vertical-align"
+);
 
 // Define other properties
-define_property!(ColorProperty, Color, "background-color");
+define_theme_property!(ColorProperty, Color, "background-color");
+define_theme_property!(BackgroundImageProperty, String, "background-image");
+define_theme_property!(BorderColorProperty, Color, "border-color");
+define_theme_property!(BorderImageProperty, BorderImage, "border-image");
+define_theme_property!(TransitionProperty, BTreeMap<String, Transition>, "transition");
+
+/// A nine-slice image drawn over the border box of an entity, used to skin panels and buttons.
+///
+/// Each part is a separate image. Corners keep the size set by `slices`, edges are stretched along
+/// one axis and the center, if any, fills the remaining space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BorderImage {
+    /// Size of the corners and edges.
+    #[serde(with = "ui_rect_serde")]
+    pub slices: UiRect<Val>,
+    pub top_left: String,
+    pub top: String,
+    pub top_right: String,
+    pub left: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center: Option<String>,
+    pub right: String,
+    pub bottom_left: String,
+    pub bottom: String,
+    pub bottom_right: String,
+}
+
+/// Registers every property defined in this module.
+pub(crate) fn register_builtin_properties(registry: &ThemePropertyRegistry) {
+    registry
+        .register::<DisplayProperty>()
+        .register::<PositionTypeProperty>()
+        .register::<DirectionProperty>()
+        .register::<FlexDirectionProperty>()
+        .register::<FlexWrapProperty>()
+        .register::<AlignItemsProperty>()
+        .register::<AlignSelfProperty>()
+        .register::<AlignContentProperty>()
+        .register::<JustifyContentProperty>()
+        .register::<PositionProperty>()
+        .register::<MarginProperty>()
+        .register::<PaddingProperty>()
+        .register::<BorderProperty>()
+        .register::<FlexGrowProperty>()
+        .register::<FlexShrinkProperty>()
+        .register::<FlexBasisProperty>()
+        .register::<SizeProperty>()
+        .register::<MinSizeProperty>()
+        .register::<MaxSizeProperty>()
+        .register::<AspectRatioProperty>()
+        .register::<OverflowProperty>()
+        .register::<TextColorProperty>()
+        .register::<TextFontPathProperty>()
+        .register::<TextFontSizeProperty>()
+        .register::<TextHorizontalAlignProperty>()
+        .register::<TextVerticalAlignProperty>()
+        .register::<ColorProperty>()
+        .register::<BackgroundImageProperty>()
+        .register::<BorderColorProperty>()
+        .register::<BorderImageProperty>()
+        .register::<TransitionProperty>();
+}
+
+/// Serde representation of `UiRect<Val>`, which doesn't implement serde traits.
+/// Missing sides default to [`Val::Undefined`].
+mod ui_rect_serde {
+    use bevy_ui::{UiRect, Val};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "UiRect", default)]
+    struct UiRectDef {
+        left: Val,
+        right: Val,
+        top: Val,
+        bottom: Val,
+    }
+
+    impl Default for UiRectDef {
+        fn default() -> Self {
+            Self {
+                left: Val::Undefined,
+                right: Val::Undefined,
+                top: Val::Undefined,
+                bottom: Val::Undefined,
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(rect: &UiRect<Val>, serializer: S) -> Result<S::Ok, S::Error> {
+        UiRectDef {
+            left: rect.left,
+            right: rect.right,
+            top: rect.top,
+            bottom: rect.bottom,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<UiRect<Val>, D::Error> {
+        let rect = UiRectDef::deserialize(deserializer)?;
+        Ok(UiRect {
+            left: rect.left,
+            right: rect.right,
+            top: rect.top,
+            bottom: rect.bottom,
+        })
+    }
+}
+
+/// Serde representation of `Size<Val>`, which doesn't implement serde traits.
+/// Missing dimensions default to [`Val::Undefined`].
+mod size_serde {
+    use bevy_ui::{Size, Val};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Size", default)]
+    struct SizeDef {
+        width: Val,
+        height: Val,
+    }
+
+    impl Default for SizeDef {
+        fn default() -> Self {
+            Self {
+                width: Val::Undefined,
+                height: Val::Undefined,
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(size: &Size<Val>, serializer: S) -> Result<S::Ok, S::Error> {
+        SizeDef {
+            width: size.width,
+            height: size.height,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Size<Val>, D::Error> {
+        let size = SizeDef::deserialize(deserializer)?;
+        Ok(Size {
+            width: size.width,
+            height: size.height,
+        })
+    }
+}
@@ -0,0 +1,478 @@
+use std::fmt;
+
+use bevy_ecs::prelude::*;
+use bevy_ui::prelude::*;
+
+use super::{ThemeNodeItem, ThemeNodeQuery};
+use crate::components::toggle::Toggle;
+
+/// A selector used as the key of theme properties, e.g. `"frame .title"`.
+///
+/// A selector is a list of compound selectors separated by whitespace. Each compound selector
+/// matches an entity, and every compound selector except the last one matches an ancestor of the
+/// entity matched by the next one. Compound selectors are made of the following parts:
+///
+/// - `name` matches entities with the `name` class or with the `name` [theme marker](ThemeMarkers)
+/// - `.class` matches entities with the `class` class
+/// - `#id` matches the entity with the `id` id
+/// - `:state` matches entities in the given [`SelectorState`], e.g. `:hover`
+///
+/// Parts can be combined without whitespace, e.g. `SliderThumbNode.large#volume:pressed`.
+///
+/// A selector can end with a [`SectionSelector`] to only target some sections of a text, e.g.
+/// `"health::1"` or `"health::value"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selector {
+    compounds: Vec<CompoundSelector>,
+    section: Option<SectionSelector>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct CompoundSelector {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    states: Vec<SelectorState>,
+}
+
+/// Interaction states that can be matched by selectors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectorState {
+    /// `:hover` matches entities with an [`Interaction`] that is hovered or clicked.
+    Hover,
+    /// `:pressed` matches entities with a clicked [`Interaction`].
+    Pressed,
+    /// `:focused` matches entities with the [`Focused`](crate::components::state::Focused) component.
+    Focused,
+    /// `:disabled` matches entities with the [`Disabled`](crate::components::state::Disabled) component.
+    Disabled,
+    /// `:on` matches entities with a [`Toggle`] that is on.
+    On,
+    /// `:off` matches entities with a [`Toggle`] that is off.
+    Off,
+    /// Any other state, which never matches and is reported by the
+    /// [`ThemeDiagnosticsPlugin`](super::ThemeDiagnosticsPlugin).
+    Unknown(String),
+}
+
+/// Sections of a [`Text`](bevy_text::Text) matched by a selector, written after `::`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectionSelector {
+    /// `::1` matches the section at the given index.
+    Index(usize),
+    /// `::tag` matches the sections with the given tag in the entity's [`TextSectionTags`](super::TextSectionTags).
+    Tag(String),
+}
+
+/// Specificity of a selector, used to decide which properties win when multiple selectors match.
+///
+/// Ids are more specific than classes and states, which are more specific than names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity {
+    pub ids: u32,
+    pub classes: u32,
+    pub names: u32,
+}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Self {
+        let (compounds, section) = match selector.split_once("::") {
+            Some((compounds, section)) => (compounds, Some(SectionSelector::parse(section.trim()))),
+            None => (selector, None),
+        };
+        let compounds = compounds
+            .split_whitespace()
+            .map(CompoundSelector::parse)
+            .collect();
+        Self { compounds, section }
+    }
+
+    /// Returns the text sections targeted by the selector, if any.
+    pub fn section(&self) -> Option<&SectionSelector> {
+        self.section.as_ref()
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        self.compounds
+            .iter()
+            .fold(Specificity::default(), |specificity, compound| {
+                Specificity {
+                    ids: specificity.ids + compound.id.is_some() as u32,
+                    classes: specificity.classes
+                        + compound.classes.len() as u32
+                        + compound.states.len() as u32,
+                    names: specificity.names + compound.name.is_some() as u32,
+                }
+            })
+    }
+
+    /// Returns `true` if the selector matches the entity, ignoring its [`SectionSelector`].
+    pub(crate) fn matches(&self, entity: Entity, query: &ThemeNodeQuery) -> bool {
+        self.matches_with(entity, query, true)
+    }
+
+    /// Returns `true` if the selector matches the entity in some state, i.e. ignoring [`SelectorState`]s.
+    pub(crate) fn can_match(&self, entity: Entity, query: &ThemeNodeQuery) -> bool {
+        self.matches_with(entity, query, false)
+    }
+
+    /// Returns the names and classes used by the selector, which can match [`ThemeKey`](super::ThemeKey) classes.
+    pub(crate) fn classes(&self) -> impl Iterator<Item = &str> {
+        self.compounds.iter().flat_map(|compound| {
+            compound
+                .name
+                .iter()
+                .chain(compound.classes.iter())
+                .map(String::as_str)
+        })
+    }
+
+    /// Returns the [`SelectorState::Unknown`] states of the selector, which never match.
+    pub(crate) fn unknown_states(&self) -> impl Iterator<Item = &str> {
+        self.compounds
+            .iter()
+            .flat_map(|compound| compound.states.iter())
+            .filter_map(|state| match state {
+                SelectorState::Unknown(state) => Some(state.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Returns the ids used by the selector.
+    pub(crate) fn ids(&self) -> impl Iterator<Item = &str> {
+        self.compounds
+            .iter()
+            .filter_map(|compound| compound.id.as_deref())
+    }
+
+    fn matches_with(&self, entity: Entity, query: &ThemeNodeQuery, check_states: bool) -> bool {
+        let (last, ancestors) = match self.compounds.split_last() {
+            Some(compounds) => compounds,
+            None => return false,
+        };
+        let mut node = match query.get(entity) {
+            Ok(node) if last.matches(&node, check_states) => node,
+            _ => return false,
+        };
+        // Descendant selectors are matched greedily from the nearest ancestor to the root.
+        let mut ancestors = ancestors.iter().rev().peekable();
+        while let Some(compound) = ancestors.peek() {
+            node = match node.parent.and_then(|parent| query.get(parent.get()).ok()) {
+                Some(parent) => parent,
+                None => return false,
+            };
+            if compound.matches(&node, check_states) {
+                ancestors.next();
+            }
+        }
+        true
+    }
+}
+
+impl CompoundSelector {
+    fn parse(compound: &str) -> Self {
+        let mut selector = Self::default();
+        let mut rest = compound;
+        let mut prefix = None;
+        loop {
+            let end = rest.find(['.', '#', ':']).unwrap_or(rest.len());
+            let (part, next) = rest.split_at(end);
+            if !part.is_empty() {
+                match prefix {
+                    None => selector.name = Some(part.to_string()),
+                    Some('.') => selector.classes.push(part.to_string()),
+                    Some(':') => selector.states.push(SelectorState::parse(part)),
+                    Some(_) => selector.id = Some(part.to_string()),
+                }
+            }
+            let mut chars = next.chars();
+            prefix = chars.next();
+            if prefix.is_none() {
+                return selector;
+            }
+            rest = chars.as_str();
+        }
+    }
+
+    fn matches(&self, node: &ThemeNodeItem, check_states: bool) -> bool {
+        let has_class = |class: &str| node.key.map_or(false, |key| key.has_class(class));
+        let name_matches = self.name.as_deref().map_or(true, |name| {
+            has_class(name) || node.markers.map_or(false, |markers| markers.contains(name))
+        });
+        let id_matches = self.id.as_deref().map_or(true, |id| {
+            node.key.and_then(|key| key.id.as_deref()) == Some(id)
+        });
+        name_matches
+            && id_matches
+            && self.classes.iter().all(|class| has_class(class))
+            && (!check_states || self.states.iter().all(|state| state.matches(node)))
+    }
+}
+
+impl SelectorState {
+    fn parse(state: &str) -> Self {
+        match state {
+            "hover" => Self::Hover,
+            "pressed" => Self::Pressed,
+            "focused" => Self::Focused,
+            "disabled" => Self::Disabled,
+            "on" => Self::On,
+            "off" => Self::Off,
+            _ => Self::Unknown(state.to_string()),
+        }
+    }
+
+    fn matches(&self, node: &ThemeNodeItem) -> bool {
+        match self {
+            Self::Hover => matches!(
+                node.interaction,
+                Some(Interaction::Hovered | Interaction::Clicked)
+            ),
+            Self::Pressed => matches!(node.interaction, Some(Interaction::Clicked)),
+            Self::Focused => node.focused.is_some(),
+            Self::Disabled => node.disabled.is_some(),
+            Self::On => matches!(node.toggle, Some(Toggle::On)),
+            Self::Off => matches!(node.toggle, Some(Toggle::Off)),
+            Self::Unknown(_) => false,
+        }
+    }
+}
+
+impl SectionSelector {
+    fn parse(section: &str) -> Self {
+        match section.parse() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Tag(section.to_string()),
+        }
+    }
+
+    /// Returns `true` if the selector matches the section at `index`, tagged with `tag`.
+    pub fn matches(&self, index: usize, tag: Option<&str>) -> bool {
+        match self {
+            Self::Index(i) => *i == index,
+            Self::Tag(t) => tag == Some(t.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for SectionSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{}", index),
+            Self::Tag(tag) => f.write_str(tag),
+        }
+    }
+}
+
+impl fmt::Display for SelectorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Hover => "hover",
+            Self::Pressed => "pressed",
+            Self::Focused => "focused",
+            Self::Disabled => "disabled",
+            Self::On => "on",
+            Self::Off => "off",
+            Self::Unknown(state) => state,
+        })
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, compound) in self.compounds.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if let Some(name) = &compound.name {
+                f.write_str(name)?;
+            }
+            for class in &compound.classes {
+                write!(f, ".{}", class)?;
+            }
+            if let Some(id) = &compound.id {
+                write!(f, "#{}", id)?;
+            }
+            for state in &compound.states {
+                write!(f, ":{}", state)?;
+            }
+        }
+        if let Some(section) = &self.section {
+            write!(f, "::{}", section)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::SystemState;
+    use bevy_hierarchy::BuildWorldChildren;
+
+    use super::*;
+    use crate::{components::state::Focused, theming::ThemeKey};
+
+    fn compound(selector: &Selector, index: usize) -> &CompoundSelector {
+        &selector.compounds[index]
+    }
+
+    #[test]
+    fn parse_compound_parts() {
+        let selector = Selector::parse("SliderThumbNode.large.dark#volume:pressed:focused");
+        assert_eq!(selector.compounds.len(), 1);
+        let thumb = compound(&selector, 0);
+        assert_eq!(thumb.name.as_deref(), Some("SliderThumbNode"));
+        assert_eq!(thumb.classes, ["large", "dark"]);
+        assert_eq!(thumb.id.as_deref(), Some("volume"));
+        assert_eq!(
+            thumb.states,
+            [SelectorState::Pressed, SelectorState::Focused]
+        );
+        assert_eq!(selector.section(), None);
+    }
+
+    #[test]
+    fn parse_descendants_and_sections() {
+        let selector = Selector::parse("  frame   .title:hover::value ");
+        assert_eq!(selector.compounds.len(), 2);
+        assert_eq!(compound(&selector, 0).name.as_deref(), Some("frame"));
+        assert_eq!(compound(&selector, 1).name, None);
+        assert_eq!(compound(&selector, 1).classes, ["title"]);
+        assert_eq!(compound(&selector, 1).states, [SelectorState::Hover]);
+        assert_eq!(
+            selector.section(),
+            Some(&SectionSelector::Tag("value".to_string()))
+        );
+        assert_eq!(
+            Selector::parse("health::1").section(),
+            Some(&SectionSelector::Index(1))
+        );
+    }
+
+    #[test]
+    fn parse_unknown_states() {
+        let selector = Selector::parse("button:hovered");
+        assert_eq!(
+            compound(&selector, 0).states,
+            [SelectorState::Unknown("hovered".to_string())]
+        );
+        assert_eq!(selector.unknown_states().collect::<Vec<_>>(), ["hovered"]);
+        assert_eq!(Selector::parse("button:hover").unknown_states().count(), 0);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for selector in [
+            "button",
+            ".primary",
+            "#save",
+            "SliderThumbNode.large#volume:pressed",
+            "frame .title:hover",
+            "health::1",
+            "TextInputText::selection",
+        ] {
+            assert_eq!(Selector::parse(selector).to_string(), selector);
+        }
+    }
+
+    #[test]
+    fn specificity_ordering() {
+        let specificity = |selector| Selector::parse(selector).specificity();
+        assert!(specificity("#save") > specificity(".a.b.c"));
+        assert!(specificity(".a.b") > specificity(".a"));
+        assert!(specificity(".a") > specificity("button"));
+        assert!(specificity("button:hover") > specificity("button"));
+        assert_eq!(specificity(".a"), specificity(":hover"));
+        assert!(specificity("frame .title") > specificity(".title"));
+        assert_eq!(
+            specificity("frame button.primary#ok:hover"),
+            Specificity {
+                ids: 1,
+                classes: 2,
+                names: 2,
+            }
+        );
+    }
+
+    /// Spawns `root` > `panel` > `button`, and returns the button.
+    fn spawn_hierarchy(world: &mut World) -> Entity {
+        let button = world
+            .spawn()
+            .insert(ThemeKey::from("button primary").with_id("ok"))
+            .insert(Interaction::Hovered)
+            .id();
+        let panel = world
+            .spawn()
+            .insert(ThemeKey::from("panel"))
+            .push_children(&[button])
+            .id();
+        world
+            .spawn()
+            .insert(ThemeKey::from("root"))
+            .push_children(&[panel]);
+        button
+    }
+
+    fn check(world: &mut World, entity: Entity, selector: &str) -> (bool, bool) {
+        let mut state = SystemState::<ThemeNodeQuery>::new(world);
+        let query = state.get(world);
+        let selector = Selector::parse(selector);
+        (
+            selector.matches(entity, &query),
+            selector.can_match(entity, &query),
+        )
+    }
+
+    #[test]
+    fn matches_classes_ids_and_descendants() {
+        let mut world = World::new();
+        let button = spawn_hierarchy(&mut world);
+        for selector in [
+            "button",
+            ".primary",
+            "button.primary#ok",
+            "panel button",
+            "root button",
+            "root panel .primary",
+        ] {
+            assert_eq!(
+                check(&mut world, button, selector),
+                (true, true),
+                "{}",
+                selector
+            );
+        }
+        for selector in [
+            "panel",
+            "button.secondary",
+            "#cancel",
+            "button panel",
+            "other button",
+            "panel root button",
+        ] {
+            assert_eq!(
+                check(&mut world, button, selector),
+                (false, false),
+                "{}",
+                selector
+            );
+        }
+    }
+
+    #[test]
+    fn states_only_affect_matches() {
+        let mut world = World::new();
+        let button = spawn_hierarchy(&mut world);
+        assert_eq!(check(&mut world, button, "button:hover"), (true, true));
+        assert_eq!(check(&mut world, button, "button:pressed"), (false, true));
+        assert_eq!(check(&mut world, button, "button:focused"), (false, true));
+        assert_eq!(
+            check(&mut world, button, "panel:hover button"),
+            (false, true)
+        );
+
+        world.entity_mut(button).insert(Focused);
+        assert_eq!(check(&mut world, button, "button:focused"), (true, true));
+        assert_eq!(check(&mut world, button, "button:hovered"), (false, true));
+    }
+}
@@ -0,0 +1,338 @@
+use std::any::TypeId;
+
+use bevy_reflect::TypeUuid;
+use bevy_utils::HashMap;
+
+use super::{
+    property_resolver, ResolvePropertyFn, Selector, Specificity, ThemeProperty, ThemePropertyName,
+    ThemeVariable, VariableRef,
+};
+
+/// A set of theme properties, indexed by selector and property name.
+///
+/// Properties can either be set to a value, or reference one of the theme's variables so that
+/// the same value can be shared by many properties.
+///
+/// Themes can be filled in code or loaded from `.theme.ron` files with the [`AssetServer`](bevy_asset::AssetServer).
+#[derive(Default, TypeUuid)]
+#[uuid = "7c62f1db-c6a7-439e-8d8a-144e2e3519bf"]
+pub struct Theme {
+    /// Rules indexed by [`RuleId`], in creation order.
+    rules: Vec<ThemeRule>,
+    rule_ids: HashMap<String, RuleId>,
+    variables: HashMap<String, ThemeVariable>,
+    /// Incremented when a rule starts or stops being used, i.e. when the selectors that can match
+    /// entities change.
+    generation: u64,
+}
+
+/// Index of a rule in a [`Theme`], used to look up properties without hashing selector strings.
+///
+/// Rules are never removed, so an id stays valid for the theme it was created for. Later rules have
+/// higher ids, which is used to break specificity ties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct RuleId(usize);
+
+/// The properties set for a selector.
+struct ThemeRule {
+    key: String,
+    selector: Selector,
+    specificity: Specificity,
+    /// Properties set to a value, along with the resolved values of references, indexed by type.
+    properties: HashMap<TypeId, Box<dyn ThemeProperty>>,
+    references: HashMap<String, PropertyReference>,
+}
+
+impl ThemeRule {
+    fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.references.is_empty()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct PropertyReference {
+    pub(crate) reference: VariableRef,
+    pub(crate) resolve: ResolvePropertyFn,
+    /// Type of the property created by `resolve`.
+    pub(crate) type_id: TypeId,
+}
+
+impl Clone for Theme {
+    fn clone(&self) -> Self {
+        Self {
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| ThemeRule {
+                    key: rule.key.clone(),
+                    selector: rule.selector.clone(),
+                    specificity: rule.specificity,
+                    properties: rule
+                        .properties
+                        .iter()
+                        .map(|(type_id, property)| (*type_id, property.box_clone()))
+                        .collect(),
+                    references: rule.references.clone(),
+                })
+                .collect(),
+            rule_ids: self.rule_ids.clone(),
+            variables: self.variables.clone(),
+            generation: self.generation,
+        }
+    }
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the property set for the exact `selector` string.
+    ///
+    /// Properties referencing a variable return the variable's value with modifiers applied.
+    pub fn get_property<T>(&self, selector: &str) -> Option<&T>
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        self.rule_ids
+            .get(selector)
+            .and_then(|id| self.rule_property::<T>(*id))
+    }
+
+    /// Sets a property for all entities matched by `selector`. See [`Selector`] for the syntax.
+    pub fn set_property<T>(&mut self, selector: &str, property: T) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        self.insert_boxed(selector, T::PROPERTY_NAME, Box::new(property));
+        self
+    }
+
+    /// Sets a property to the value of a variable for all entities matched by `selector`.
+    ///
+    /// The property follows the variable when it changes, and is unset while the variable is
+    /// missing or has a type that can't be used for the property.
+    pub fn set_property_ref<T>(&mut self, selector: &str, reference: VariableRef) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        self.insert_reference(
+            selector,
+            T::PROPERTY_NAME,
+            PropertyReference {
+                reference,
+                resolve: property_resolver::<T>(),
+                type_id: TypeId::of::<T>(),
+            },
+        );
+        self
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<&ThemeVariable> {
+        self.variables.get(name)
+    }
+
+    /// Sets a variable and updates the properties referencing it.
+    pub fn set_variable(&mut self, name: &str, value: ThemeVariable) -> &mut Self {
+        self.variables.insert(name.to_string(), value);
+        self.resolve_references(name);
+        self
+    }
+
+    /// Removes a variable and unsets the properties referencing it.
+    pub fn remove_variable(&mut self, name: &str) -> &mut Self {
+        self.variables.remove(name);
+        self.resolve_references(name);
+        self
+    }
+
+    /// Iterates over every variable along with its name.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &ThemeVariable)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Sets a property from its name, e.g. `"background-color"`.
+    pub(crate) fn insert_boxed(
+        &mut self,
+        selector: &str,
+        property_name: &str,
+        property: Box<dyn ThemeProperty>,
+    ) {
+        let rule = self.rule_mut(selector);
+        rule.references.remove(property_name);
+        rule.properties
+            .insert(property.as_any().type_id(), property);
+    }
+
+    /// Sets a property referencing a variable from its name, e.g. `"background-color"`.
+    pub(crate) fn insert_reference(
+        &mut self,
+        selector: &str,
+        property_name: &str,
+        reference: PropertyReference,
+    ) {
+        let resolved = self
+            .variables
+            .get(&reference.reference.variable)
+            .and_then(|variable| reference.reference.resolve(variable))
+            .and_then(|variable| (reference.resolve)(&variable));
+        let rule = self.rule_mut(selector);
+        match resolved {
+            Some(property) => rule.properties.insert(reference.type_id, property),
+            None => rule.properties.remove(&reference.type_id),
+        };
+        rule.references.insert(property_name.to_string(), reference);
+    }
+
+    /// Removes a property from its name, e.g. `"background-color"`.
+    pub(crate) fn remove_boxed(&mut self, selector: &str, property_name: &str) {
+        let rule = match self.rule_ids.get(selector) {
+            Some(id) => &mut self.rules[id.0],
+            None => return,
+        };
+        let type_id = match rule.references.remove(property_name) {
+            Some(reference) => Some(reference.type_id),
+            None => rule
+                .properties
+                .iter()
+                .find(|(_, property)| property.name() == property_name)
+                .map(|(type_id, _)| *type_id),
+        };
+        if let Some(type_id) = type_id {
+            rule.properties.remove(&type_id);
+            if rule.is_empty() {
+                self.generation += 1;
+            }
+        }
+    }
+
+    /// Iterates over every property set to a value along with its selector and property name.
+    ///
+    /// Properties referencing a variable are iterated by [`Theme::references`] instead.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &dyn ThemeProperty)> {
+        self.rules.iter().flat_map(|rule| {
+            rule.properties
+                .iter()
+                .filter(move |(type_id, _)| {
+                    !rule
+                        .references
+                        .values()
+                        .any(|reference| reference.type_id == **type_id)
+                })
+                .map(move |(_, property)| (rule.key.as_str(), property.name(), property.as_ref()))
+        })
+    }
+
+    /// Iterates over every property referencing a variable along with its selector and property name.
+    pub fn references(&self) -> impl Iterator<Item = (&str, &str, &VariableRef)> {
+        self.references_with_resolvers()
+            .map(|(selector, name, reference)| (selector, name, &reference.reference))
+    }
+
+    pub(crate) fn references_with_resolvers(
+        &self,
+    ) -> impl Iterator<Item = (&str, &str, &PropertyReference)> {
+        self.rules.iter().flat_map(|rule| {
+            rule.references
+                .iter()
+                .map(move |(name, reference)| (rule.key.as_str(), name.as_str(), reference))
+        })
+    }
+
+    /// Iterates over the id, selector string and parsed selector of every rule that sets properties.
+    pub(crate) fn selectors(&self) -> impl Iterator<Item = (RuleId, &str, &Selector)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| !rule.is_empty())
+            .map(|(i, rule)| (RuleId(i), rule.key.as_str(), &rule.selector))
+    }
+
+    /// Returns the precedence of a rule. Rules with a higher precedence win over rules with a lower
+    /// one when both match an entity.
+    pub(crate) fn precedence(&self, id: RuleId) -> (Specificity, RuleId) {
+        (self.rules[id.0].specificity, id)
+    }
+
+    pub(crate) fn rule_id(&self, selector: &str) -> Option<RuleId> {
+        self.rule_ids.get(selector).copied()
+    }
+
+    pub(crate) fn rule_key(&self, id: RuleId) -> &str {
+        &self.rules[id.0].key
+    }
+
+    /// Iterates over the properties of a rule, including the resolved values of references.
+    pub(crate) fn rule_properties(&self, id: RuleId) -> impl Iterator<Item = &dyn ThemeProperty> {
+        self.rules[id.0]
+            .properties
+            .values()
+            .map(|property| property.as_ref())
+    }
+
+    pub(crate) fn rule_selector(&self, id: RuleId) -> &Selector {
+        &self.rules[id.0].selector
+    }
+
+    /// Gets the `T` property set by a rule, without hashing its selector.
+    pub(crate) fn rule_property<T>(&self, id: RuleId) -> Option<&T>
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        self.rules[id.0]
+            .properties
+            .get(&TypeId::of::<T>())
+            .and_then(|property| property.as_any().downcast_ref::<T>())
+    }
+
+    /// Returns a number that changes when the selectors returned by [`Theme::selectors`] change.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn rule_mut(&mut self, selector: &str) -> &mut ThemeRule {
+        let id = match self.rule_ids.get(selector) {
+            Some(id) => *id,
+            None => {
+                let id = RuleId(self.rules.len());
+                let parsed = Selector::parse(selector);
+                self.rules.push(ThemeRule {
+                    key: selector.to_string(),
+                    specificity: parsed.specificity(),
+                    selector: parsed,
+                    properties: HashMap::default(),
+                    references: HashMap::default(),
+                });
+                self.rule_ids.insert(selector.to_string(), id);
+                id
+            }
+        };
+        // The rule is about to set a property, so it can start matching entities.
+        if self.rules[id.0].is_empty() {
+            self.generation += 1;
+        }
+        &mut self.rules[id.0]
+    }
+
+    /// Resolves the properties referencing the named variable again.
+    fn resolve_references(&mut self, variable_name: &str) {
+        let variable = self.variables.get(variable_name);
+        for rule in self.rules.iter_mut() {
+            for reference in rule
+                .references
+                .values()
+                .filter(|reference| reference.reference.variable == variable_name)
+            {
+                let resolved = variable
+                    .and_then(|variable| reference.reference.resolve(variable))
+                    .and_then(|variable| (reference.resolve)(&variable));
+                match resolved {
+                    Some(property) => rule.properties.insert(reference.type_id, property),
+                    None => rule.properties.remove(&reference.type_id),
+                };
+            }
+        }
+    }
+}
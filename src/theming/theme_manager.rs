@@ -1,37 +1,372 @@
-use bevy_utils::HashMap;
+use std::{fs, path::Path};
 
-use super::{ThemeProperty, ThemePropertyName};
+use bevy_asset::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_utils::{HashMap, HashSet};
 
+use super::{
+    RuleId, Theme, ThemeExportError, ThemeNodeQuery, ThemeProperty, ThemePropertyName,
+    ThemeVariable, ThemeVariant, VariableRef,
+};
+
+/// Holds every named theme and the name of the active one.
+///
+/// Properties of the active theme are applied to the entities matched by their [`Selector`](super::Selector).
+/// Switching the active theme applies all of its properties at once.
 pub struct ThemeManager {
-    property_store: HashMap<String, Box<dyn ThemeProperty>>,
+    themes: HashMap<String, Theme>,
+    active: String,
+    sources: HashMap<String, ThemeSource>,
+    /// Rules of the active theme whose properties changed since they were last applied.
+    changed_rules: HashSet<RuleId>,
+    all_selectors_changed: bool,
+    /// Incremented when the active theme is switched or replaced, which invalidates its [`RuleId`]s.
+    epoch: u64,
+}
+
+/// A theme asset used as the source of properties for a named theme.
+struct ThemeSource {
+    handle: Handle<Theme>,
+    /// Selectors and property names of the properties copied from the asset.
+    keys: HashSet<(String, String)>,
+    /// Names of the variables copied from the asset.
+    variables: HashSet<String>,
+    pending: bool,
+}
+
+/// A property applied to an entity, see [`ThemeManager::resolved_properties`].
+pub(crate) struct ResolvedProperty<'t> {
+    pub selector: &'t str,
+    pub name: &'static str,
+    pub property: &'t dyn ThemeProperty,
+}
+
+impl<'t> ResolvedProperty<'t> {
+    /// Finds the `T` property in a list of resolved properties.
+    pub(crate) fn find<T>(properties: &[ResolvedProperty<'t>]) -> Option<(&'t str, &'t T)>
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        properties
+            .iter()
+            .filter(|resolved| resolved.name == T::PROPERTY_NAME)
+            .find_map(|resolved| {
+                resolved
+                    .property
+                    .as_any()
+                    .downcast_ref::<T>()
+                    .map(|property| (resolved.selector, property))
+            })
+    }
+}
+
+impl Default for ThemeManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ThemeManager {
+    /// The name of the theme that is active by default.
+    pub const DEFAULT_THEME: &'static str = "default";
+
+    /// Creates a theme manager with an empty default theme.
     pub fn new() -> Self {
+        Self::with_default_theme(Theme::new())
+    }
+
+    /// Creates a theme manager whose default theme is the [built-in theme](Theme::builtin).
+    ///
+    /// The built-in theme colours every widget part by its marker, so it overrides colours set
+    /// with the `*_bundle` methods of widget builders. Insert it before adding the
+    /// [`ThemingPlugin`](super::ThemingPlugin) to opt in:
+    ///
+    /// ```ignore
+    /// app.insert_resource(ThemeManager::with_builtin(ThemeVariant::Dark))
+    ///     .add_plugins(AllWidgetsPlugins);
+    /// ```
+    pub fn with_builtin(variant: ThemeVariant) -> Self {
+        Self::with_default_theme(Theme::builtin(variant))
+    }
+
+    fn with_default_theme(theme: Theme) -> Self {
+        let mut themes = HashMap::default();
+        themes.insert(Self::DEFAULT_THEME.to_string(), theme);
         Self {
-            property_store: HashMap::new(),
+            themes,
+            active: Self::DEFAULT_THEME.to_string(),
+            sources: HashMap::default(),
+            changed_rules: HashSet::default(),
+            all_selectors_changed: false,
+            epoch: 0,
         }
     }
 
-    pub fn get_property<T>(&self, key: &str) -> Option<&T>
+    /// Gets a property set for the exact `selector` string in the active theme.
+    pub fn get_property<T>(&self, selector: &str) -> Option<&T>
     where
         T: 'static + ThemeProperty + ThemePropertyName,
     {
-        self.property_store
-            .get(&format!("{}:{}", key, T::PROPERTY_NAME))
-            .and_then(|result| {
-                result.as_any().downcast_ref::<T>()
-            })
+        self.active_theme().get_property(selector)
+    }
+
+    /// Sets a property for all entities matched by `selector` in the active theme.
+    pub fn set_property<T>(&mut self, selector: &str, property: T) -> &mut Self
+    where
+        T: 'static + ThemeProperty + ThemePropertyName,
+    {
+        if let Some(theme) = self.themes.get_mut(&self.active) {
+            theme.set_property(selector, property);
+        }
+        self.mark_changed(selector);
+        self
     }
 
-    pub fn set_property<T>(&mut self, key: &str, property: T) -> &mut Self
+    /// Sets a property to the value of a variable for all entities matched by `selector` in the active theme.
+    pub fn set_property_ref<T>(&mut self, selector: &str, reference: VariableRef) -> &mut Self
     where
         T: 'static + ThemeProperty + ThemePropertyName,
     {
-        self.property_store.insert(
-            format!("{}:{}", key, T::PROPERTY_NAME),
-            Box::new(property),
+        if let Some(theme) = self.themes.get_mut(&self.active) {
+            theme.set_property_ref::<T>(selector, reference);
+        }
+        self.mark_changed(selector);
+        self
+    }
+
+    /// Gets a variable from the active theme.
+    pub fn get_variable(&self, name: &str) -> Option<&ThemeVariable> {
+        self.active_theme().get_variable(name)
+    }
+
+    /// Sets a variable in the active theme and updates the properties referencing it.
+    pub fn set_variable(&mut self, name: &str, value: ThemeVariable) -> &mut Self {
+        if let Some(theme) = self.themes.get_mut(&self.active) {
+            theme.set_variable(name, value);
+        }
+        self.all_selectors_changed = true;
+        self
+    }
+
+    /// Returns the name of the active theme.
+    pub fn active_theme_name(&self) -> &str {
+        &self.active
+    }
+
+    /// Returns the active theme.
+    pub fn active_theme(&self) -> &Theme {
+        &self.themes[&self.active]
+    }
+
+    /// Changes the active theme. The theme is created empty if it doesn't exist yet.
+    pub fn set_active_theme(&mut self, name: &str) -> &mut Self {
+        self.themes.entry(name.to_string()).or_default();
+        if self.active != name {
+            self.active = name.to_string();
+            self.all_selectors_changed = true;
+            self.epoch += 1;
+        }
+        self
+    }
+
+    /// Returns the theme with the given name, if it exists.
+    pub fn theme(&self, name: &str) -> Option<&Theme> {
+        self.themes.get(name)
+    }
+
+    /// Returns the theme with the given name. The theme is created empty if it doesn't exist yet.
+    ///
+    /// Getting the active theme this way reapplies all of its properties.
+    pub fn theme_mut(&mut self, name: &str) -> &mut Theme {
+        if self.active == name {
+            self.all_selectors_changed = true;
+            self.epoch += 1;
+        }
+        self.themes.entry(name.to_string()).or_default()
+    }
+
+    /// Adds or replaces a named theme.
+    pub fn insert_theme(&mut self, name: &str, theme: Theme) -> &mut Self {
+        self.themes.insert(name.to_string(), theme);
+        if self.active == name {
+            self.all_selectors_changed = true;
+            self.epoch += 1;
+        }
+        self
+    }
+
+    /// Iterates over the names of every theme.
+    pub fn theme_names(&self) -> impl Iterator<Item = &str> {
+        self.themes.keys().map(|name| name.as_str())
+    }
+
+    /// Saves the named theme to a `.theme.ron` file, which can be loaded back as a theme asset.
+    ///
+    /// This includes properties set in code as well as the ones copied from the theme's source.
+    pub fn save_theme(&self, name: &str, path: impl AsRef<Path>) -> Result<(), ThemeExportError> {
+        let theme = self
+            .theme(name)
+            .ok_or_else(|| ThemeExportError::UnknownTheme(name.to_string()))?;
+        let contents = theme.to_ron().map_err(ThemeExportError::Serialize)?;
+        fs::write(path, contents).map_err(ThemeExportError::Io)
+    }
+
+    /// Uses a theme asset as the source of properties for the named theme.
+    ///
+    /// Properties from the asset are copied once it is loaded, and again every time it is modified
+    /// when the asset server watches for changes. Properties are overwritten by the last one set,
+    /// whether it was set in code or loaded from the asset.
+    pub fn set_theme_source(&mut self, name: &str, handle: Handle<Theme>) -> &mut Self {
+        self.themes.entry(name.to_string()).or_default();
+        self.sources.insert(
+            name.to_string(),
+            ThemeSource {
+                handle,
+                keys: HashSet::default(),
+                variables: HashSet::default(),
+                pending: true,
+            },
         );
         self
     }
+
+    /// Returns the handle of the theme asset used as the source of the named theme, if any.
+    pub fn theme_source(&self, name: &str) -> Option<&Handle<Theme>> {
+        self.sources.get(name).map(|source| &source.handle)
+    }
+
+    /// Returns the names and source handles of the themes that need properties copied from their
+    /// source, either because it was just set or because it is one of the `modified` assets.
+    pub(crate) fn sources_to_update(
+        &self,
+        modified: &[Handle<Theme>],
+    ) -> Vec<(String, Handle<Theme>)> {
+        self.sources
+            .iter()
+            .filter(|(_, source)| source.pending || modified.contains(&source.handle))
+            .map(|(name, source)| (name.clone(), source.handle.clone()))
+            .collect()
+    }
+
+    /// Replaces the properties previously copied from the named theme's source with the properties of `asset`.
+    pub(crate) fn apply_source(&mut self, name: &str, asset: &Theme) {
+        let (theme, source) = match (self.themes.get_mut(name), self.sources.get_mut(name)) {
+            (Some(theme), Some(source)) => (theme, source),
+            _ => return,
+        };
+        let is_active = self.active == name;
+        for (selector, property_name) in source.keys.drain() {
+            theme.remove_boxed(&selector, &property_name);
+        }
+        for variable in source.variables.drain() {
+            theme.remove_variable(&variable);
+        }
+        for (variable, value) in asset.variables() {
+            theme.set_variable(variable, value.clone());
+            source.variables.insert(variable.to_string());
+        }
+        for (selector, property_name, property) in asset.iter() {
+            theme.insert_boxed(selector, property_name, property.box_clone());
+            source
+                .keys
+                .insert((selector.to_string(), property_name.to_string()));
+        }
+        for (selector, property_name, reference) in asset.references_with_resolvers() {
+            theme.insert_reference(selector, property_name, reference.clone());
+            source
+                .keys
+                .insert((selector.to_string(), property_name.to_string()));
+        }
+        if is_active {
+            // Variables can be referenced by any selector, including ones set in code.
+            self.all_selectors_changed = true;
+        }
+        source.pending = false;
+    }
+
+    /// Returns the rules of the active theme that match the entity, by decreasing precedence.
+    ///
+    /// Rules targeting text sections are left out, see [`ThemeManager::matching_section_rules`].
+    pub(crate) fn matching_rules(&self, entity: Entity, query: &ThemeNodeQuery) -> Vec<RuleId> {
+        self.matching(entity, query, false)
+    }
+
+    /// Returns the rules of the active theme that target text sections of the entity, by
+    /// decreasing precedence.
+    pub(crate) fn matching_section_rules(
+        &self,
+        entity: Entity,
+        query: &ThemeNodeQuery,
+    ) -> Vec<RuleId> {
+        self.matching(entity, query, true)
+    }
+
+    fn matching(&self, entity: Entity, query: &ThemeNodeQuery, sections: bool) -> Vec<RuleId> {
+        let theme = self.active_theme();
+        let mut rules: Vec<_> = theme
+            .selectors()
+            .filter(|(_, _, selector)| {
+                selector.section().is_some() == sections && selector.matches(entity, query)
+            })
+            .map(|(id, _, _)| id)
+            .collect();
+        rules.sort_unstable_by_key(|id| std::cmp::Reverse(theme.precedence(*id)));
+        rules
+    }
+
+    /// Returns the properties of the active theme that apply to the entity, along with the
+    /// selector that set them, sorted by property name.
+    ///
+    /// Only the entity's own rules are used, so properties inherited from ancestors and properties
+    /// of text sections are left out.
+    pub(crate) fn resolved_properties(
+        &self,
+        entity: Entity,
+        query: &ThemeNodeQuery,
+    ) -> Vec<ResolvedProperty> {
+        let theme = self.active_theme();
+        let mut properties: Vec<ResolvedProperty> = Vec::new();
+        for rule in self.matching_rules(entity, query) {
+            for property in theme.rule_properties(rule) {
+                if !properties
+                    .iter()
+                    .any(|resolved| resolved.name == property.name())
+                {
+                    properties.push(ResolvedProperty {
+                        selector: theme.rule_key(rule),
+                        name: property.name(),
+                        property,
+                    });
+                }
+            }
+        }
+        properties.sort_unstable_by_key(|resolved| resolved.name);
+        properties
+    }
+
+    /// Returns a value that changes when the rules of the active theme that can match entities
+    /// change, in which case matched rules need to be found again.
+    pub(crate) fn rules_version(&self) -> (u64, u64) {
+        (self.epoch, self.active_theme().generation())
+    }
+
+    /// Returns `true` if properties of the given rule changed since the last time they were applied.
+    pub(crate) fn is_rule_changed(&self, rule: RuleId) -> bool {
+        self.all_selectors_changed || self.changed_rules.contains(&rule)
+    }
+
+    pub(crate) fn has_changed_selectors(&self) -> bool {
+        self.all_selectors_changed || !self.changed_rules.is_empty()
+    }
+
+    pub(crate) fn clear_changed_selectors(&mut self) {
+        self.changed_rules.clear();
+        self.all_selectors_changed = false;
+    }
+
+    fn mark_changed(&mut self, selector: &str) {
+        if let Some(id) = self.active_theme().rule_id(selector) {
+            self.changed_rules.insert(id);
+        }
+    }
 }
@@ -0,0 +1,398 @@
+use std::any::{Any, TypeId};
+
+use bevy_ecs::{prelude::*, system::Command};
+use bevy_render::prelude::*;
+use bevy_ui::{Size, UiRect, Val};
+use bevy_utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::{ThemePropertyName, TransitionProperty};
+
+/// Describes how a property is animated when its value changes.
+///
+/// Transitions are set per property name with a [`TransitionProperty`], e.g. in a theme file:
+///
+/// ```ron
+/// "button:transition": {
+///     "background-color": (duration: 0.2, easing: EaseOut),
+///     "size": (duration: 0.5, delay: 0.1),
+/// },
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transition {
+    /// Duration of the animation, in seconds.
+    pub duration: f32,
+    /// Time to wait before starting the animation, in seconds.
+    pub delay: f32,
+    pub easing: Easing,
+}
+
+/// Easing curves used by transitions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl Easing {
+    /// Maps linear progress between `0.0` and `1.0` to eased progress.
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Values that can be interpolated by transitions.
+pub trait Animatable: Clone + PartialEq + Send + Sync + 'static {
+    /// Interpolates between `self` and `target`, where `t` goes from `0.0` to `1.0`.
+    fn interpolate(&self, target: &Self, t: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn interpolate(&self, target: &Self, t: f32) -> Self {
+        self + (target - self) * t
+    }
+}
+
+impl Animatable for Color {
+    fn interpolate(&self, target: &Self, t: f32) -> Self {
+        let [r, g, b, a] = self.as_rgba_f32();
+        let [target_r, target_g, target_b, target_a] = target.as_rgba_f32();
+        Color::rgba(
+            r.interpolate(&target_r, t),
+            g.interpolate(&target_g, t),
+            b.interpolate(&target_b, t),
+            a.interpolate(&target_a, t),
+        )
+    }
+}
+
+impl Animatable for Val {
+    /// Values with different units can't be interpolated, so they switch halfway through.
+    fn interpolate(&self, target: &Self, t: f32) -> Self {
+        match (self, target) {
+            (Val::Px(a), Val::Px(b)) => Val::Px(a.interpolate(b, t)),
+            (Val::Percent(a), Val::Percent(b)) => Val::Percent(a.interpolate(b, t)),
+            _ if t < 0.5 => *self,
+            _ => *target,
+        }
+    }
+}
+
+impl Animatable for UiRect<Val> {
+    fn interpolate(&self, target: &Self, t: f32) -> Self {
+        UiRect {
+            left: self.left.interpolate(&target.left, t),
+            right: self.right.interpolate(&target.right, t),
+            top: self.top.interpolate(&target.top, t),
+            bottom: self.bottom.interpolate(&target.bottom, t),
+        }
+    }
+}
+
+impl Animatable for Size<Val> {
+    fn interpolate(&self, target: &Self, t: f32) -> Self {
+        Size {
+            width: self.width.interpolate(&target.width, t),
+            height: self.height.interpolate(&target.height, t),
+        }
+    }
+}
+
+/// Transitions currently running on an entity, indexed by property type and by the index of the
+/// animated part, e.g. a text section.
+///
+/// This is added automatically when a property with a transition is first applied, and keeps the
+/// last value applied to each of these properties to detect when they change.
+#[derive(Component, Default)]
+pub struct ThemeTransitions {
+    targets: HashMap<(TypeId, usize), Box<dyn Any + Send + Sync>>,
+    tweens: HashMap<(TypeId, usize), Tween>,
+}
+
+struct Tween {
+    from: Box<dyn Any + Send + Sync>,
+    start: f64,
+    transition: Transition,
+    /// Whether the tween was animated since the last call to [`clear_stale_transitions`].
+    touched: bool,
+}
+
+impl ThemeTransitions {
+    /// Returns `true` if any transition is running.
+    pub fn is_animating(&self) -> bool {
+        !self.tweens.is_empty()
+    }
+
+    /// Returns the value a property should have at time `now` while moving from `current` to `target`.
+    ///
+    /// A transition starts when `target` changes, and continues from the current value when a
+    /// running transition is interrupted by a new target. The first value applied to a property,
+    /// e.g. when the entity is spawned, is applied without a transition.
+    fn animate<T: Animatable>(
+        &mut self,
+        key: (TypeId, usize),
+        current: T,
+        target: T,
+        transition: Option<&Transition>,
+        now: f64,
+    ) -> T {
+        let transition = match transition {
+            Some(transition) if transition.duration > 0.0 || transition.delay > 0.0 => transition,
+            _ => {
+                self.targets.remove(&key);
+                self.tweens.remove(&key);
+                return target;
+            }
+        };
+        let previous = self
+            .targets
+            .insert(key, Box::new(target.clone()))
+            .map(|previous| previous.downcast_ref::<T>() == Some(&target));
+        match previous {
+            None => {
+                self.tweens.remove(&key);
+                return target;
+            }
+            Some(false) if current != target => {
+                self.tweens.insert(
+                    key,
+                    Tween {
+                        from: Box::new(current),
+                        start: now,
+                        transition: transition.clone(),
+                        touched: true,
+                    },
+                );
+            }
+            Some(false) => {
+                self.tweens.remove(&key);
+                return target;
+            }
+            Some(true) => {}
+        }
+
+        let tween = match self.tweens.get_mut(&key) {
+            Some(tween) => tween,
+            None => return target,
+        };
+        tween.touched = true;
+        let transition = &tween.transition;
+        let elapsed = (now - tween.start) as f32 - transition.delay;
+        let t = if transition.duration > 0.0 {
+            (elapsed / transition.duration).clamp(0.0, 1.0)
+        } else if elapsed >= 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+        if t >= 1.0 {
+            self.tweens.remove(&key);
+            return target;
+        }
+        match tween.from.downcast_ref::<T>() {
+            Some(from) => from.interpolate(&target, transition.easing.apply(t)),
+            None => target,
+        }
+    }
+}
+
+/// Whether any entity has a running transition, updated by [`clear_stale_transitions`].
+///
+/// The systems animating properties skip every entity when no rule changed, unless transitions are
+/// running.
+#[derive(Default)]
+pub(crate) struct RunningTransitions(pub(crate) bool);
+
+/// Drops the transitions that weren't animated this frame, e.g. because the property they animate
+/// is no longer set for the entity, so that they don't keep the entity animating.
+///
+/// Entities with running transitions are updated by every system that animates properties, so a
+/// transition that is still needed is always animated.
+pub(crate) fn clear_stale_transitions(
+    mut query: Query<&mut ThemeTransitions>,
+    mut running: ResMut<RunningTransitions>,
+) {
+    running.0 = false;
+    for mut transitions in query.iter_mut() {
+        if !transitions.is_animating() {
+            continue;
+        }
+        let transitions = &mut *transitions;
+        let targets = &mut transitions.targets;
+        transitions.tweens.retain(|key, tween| {
+            if !tween.touched {
+                targets.remove(key);
+            }
+            std::mem::replace(&mut tween.touched, false)
+        });
+        running.0 |= transitions.is_animating();
+    }
+}
+
+/// Animates the properties applied to an entity according to the transitions set for it.
+pub(crate) struct TransitionAnimator<'a> {
+    transitions: Option<Mut<'a, ThemeTransitions>>,
+    added: ThemeTransitions,
+    specs: Option<&'a TransitionProperty>,
+    now: f64,
+}
+
+impl<'a> TransitionAnimator<'a> {
+    pub(crate) fn new(
+        transitions: Option<Mut<'a, ThemeTransitions>>,
+        specs: Option<&'a TransitionProperty>,
+        now: f64,
+    ) -> Self {
+        Self {
+            transitions,
+            added: ThemeTransitions::default(),
+            specs,
+            now,
+        }
+    }
+
+    /// Returns the value of the `P` property moving from `current` towards `target`.
+    pub(crate) fn animate<P, T>(&mut self, current: T, target: T) -> T
+    where
+        P: 'static + ThemePropertyName,
+        T: Animatable,
+    {
+        self.animate_part::<P, T>(0, current, target)
+    }
+
+    /// Same as [`TransitionAnimator::animate`] for a property applied to several parts of an
+    /// entity, e.g. text sections, where `part` is the index of the part being animated.
+    pub(crate) fn animate_part<P, T>(&mut self, part: usize, current: T, target: T) -> T
+    where
+        P: 'static + ThemePropertyName,
+        T: Animatable,
+    {
+        let transition = self.specs.and_then(|specs| specs.0.get(P::PROPERTY_NAME));
+        let transitions = match &mut self.transitions {
+            Some(transitions) => &mut **transitions,
+            None => &mut self.added,
+        };
+        let key = (TypeId::of::<P>(), part);
+        transitions.animate(key, current, target, transition, self.now)
+    }
+
+    /// Adds the transitions that started on an entity that didn't have [`ThemeTransitions`] yet.
+    pub(crate) fn finish(self, entity: Entity, commands: &mut Commands) {
+        if !self.added.targets.is_empty() {
+            commands.add(AddTransitions {
+                entity,
+                transitions: self.added,
+            });
+        }
+    }
+}
+
+/// Merges transitions into an entity's [`ThemeTransitions`].
+///
+/// This is done with a command so that transitions started by different systems on the same frame
+/// don't overwrite each other.
+struct AddTransitions {
+    entity: Entity,
+    transitions: ThemeTransitions,
+}
+
+impl Command for AddTransitions {
+    fn write(self, world: &mut World) {
+        let mut entity = match world.get_entity_mut(self.entity) {
+            Some(entity) => entity,
+            None => return,
+        };
+        match entity.get_mut::<ThemeTransitions>() {
+            Some(mut transitions) => {
+                transitions.targets.extend(self.transitions.targets);
+                transitions.tweens.extend(self.transitions.tweens);
+            }
+            None => {
+                entity.insert(self.transitions);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> (TypeId, usize) {
+        (TypeId::of::<f32>(), 0)
+    }
+
+    fn transition() -> Transition {
+        Transition {
+            duration: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_value_is_applied_without_transition() {
+        let mut transitions = ThemeTransitions::default();
+        let value = transitions.animate(key(), 0.0, 10.0, Some(&transition()), 0.0);
+        assert_eq!(value, 10.0);
+        assert!(!transitions.is_animating());
+    }
+
+    #[test]
+    fn changed_values_are_animated() {
+        let mut transitions = ThemeTransitions::default();
+        transitions.animate(key(), 0.0, 10.0, Some(&transition()), 0.0);
+        let value = transitions.animate(key(), 10.0, 20.0, Some(&transition()), 1.0);
+        assert_eq!(value, 10.0);
+        assert!(transitions.is_animating());
+        let value = transitions.animate(key(), value, 20.0, Some(&transition()), 1.5);
+        assert_eq!(value, 15.0);
+        let value = transitions.animate(key(), value, 20.0, Some(&transition()), 2.0);
+        assert_eq!(value, 20.0);
+        assert!(!transitions.is_animating());
+    }
+
+    #[test]
+    fn values_without_transition_are_applied_immediately() {
+        let mut transitions = ThemeTransitions::default();
+        transitions.animate(key(), 0.0, 10.0, Some(&transition()), 0.0);
+        transitions.animate(key(), 10.0, 20.0, Some(&transition()), 0.0);
+        let value = transitions.animate(key(), 10.0, 30.0, None, 0.5);
+        assert_eq!(value, 30.0);
+        assert!(!transitions.is_animating());
+    }
+
+    #[test]
+    fn untouched_transitions_are_dropped() {
+        let mut transitions = ThemeTransitions::default();
+        transitions.animate(key(), 0.0, 10.0, Some(&transition()), 0.0);
+        transitions.animate(key(), 10.0, 20.0, Some(&transition()), 0.0);
+        let mut world = World::new();
+        let entity = world.spawn().insert(transitions).id();
+        let mut stage = SystemStage::single(clear_stale_transitions);
+
+        let is_animating = |world: &World| {
+            world
+                .get::<ThemeTransitions>(entity)
+                .unwrap()
+                .is_animating()
+        };
+        stage.run(&mut world);
+        assert!(is_animating(&world));
+        stage.run(&mut world);
+        assert!(!is_animating(&world));
+    }
+}
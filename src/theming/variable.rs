@@ -0,0 +1,294 @@
+use std::any::Any;
+
+use bevy_render::prelude::*;
+use bevy_ui::{Size, UiRect, Val};
+use serde::{Deserialize, Serialize};
+
+use super::{ThemeProperty, ThemePropertyName};
+
+/// A named value that theme properties can reference, e.g. a palette colour or a spacing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ThemeVariable {
+    Color(Color),
+    Number(f32),
+    Val(Val),
+    String(String),
+}
+
+impl ThemeVariable {
+    /// Converts the variable into `T`, the inner type of a property.
+    ///
+    /// Besides their own type, numbers can be used as pixel values and values can be used for all
+    /// sides of a [`UiRect`] or both dimensions of a [`Size`].
+    pub fn convert<T: 'static + Clone>(&self) -> Option<T> {
+        let value: Box<dyn Any> = match self {
+            ThemeVariable::Color(color) => Box::new(*color),
+            ThemeVariable::Number(number) => Box::new(*number),
+            ThemeVariable::Val(val) => Box::new(*val),
+            ThemeVariable::String(string) => Box::new(string.clone()),
+        };
+        if let Some(value) = value.downcast_ref::<T>() {
+            return Some(value.clone());
+        }
+        let val = match self {
+            ThemeVariable::Number(number) => Val::Px(*number),
+            ThemeVariable::Val(val) => *val,
+            _ => return None,
+        };
+        let candidates: [Box<dyn Any>; 3] = [
+            Box::new(val),
+            Box::new(UiRect::all(val)),
+            Box::new(Size::new(val, val)),
+        ];
+        candidates
+            .iter()
+            .find_map(|candidate| candidate.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Applies a modifier, returning `None` if it doesn't apply to this kind of variable.
+    pub fn modify(&self, modifier: &ValueModifier) -> Option<Self> {
+        match (self, modifier) {
+            (ThemeVariable::Color(color), ValueModifier::Lighten(amount)) => {
+                Some(ThemeVariable::Color(lighten(*color, *amount)))
+            }
+            (ThemeVariable::Color(color), ValueModifier::Darken(amount)) => {
+                Some(ThemeVariable::Color(lighten(*color, -amount)))
+            }
+            (ThemeVariable::Color(color), ValueModifier::Alpha(alpha)) => {
+                let mut color = *color;
+                color.set_a(*alpha);
+                Some(ThemeVariable::Color(color))
+            }
+            (ThemeVariable::Number(number), ValueModifier::Scale(scale)) => {
+                Some(ThemeVariable::Number(number * scale))
+            }
+            (ThemeVariable::Val(Val::Px(px)), ValueModifier::Scale(scale)) => {
+                Some(ThemeVariable::Val(Val::Px(px * scale)))
+            }
+            (ThemeVariable::Val(Val::Percent(percent)), ValueModifier::Scale(scale)) => {
+                Some(ThemeVariable::Val(Val::Percent(percent * scale)))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn lighten(color: Color, amount: f32) -> Color {
+    match color.as_hsla() {
+        Color::Hsla {
+            hue,
+            saturation,
+            lightness,
+            alpha,
+        } => Color::hsla(hue, saturation, (lightness + amount).clamp(0.0, 1.0), alpha),
+        _ => color,
+    }
+}
+
+/// Derives a new value from a variable.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ValueModifier {
+    /// Increases the lightness of a colour.
+    Lighten(f32),
+    /// Decreases the lightness of a colour.
+    Darken(f32),
+    /// Replaces the alpha of a colour.
+    Alpha(f32),
+    /// Multiplies a number or a pixel or percent value.
+    Scale(f32),
+}
+
+/// A property value taken from a variable, with optional modifiers applied in order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariableRef {
+    pub variable: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<ValueModifier>,
+}
+
+impl VariableRef {
+    pub fn new(variable: impl Into<String>) -> Self {
+        Self {
+            variable: variable.into(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    pub fn lighten(mut self, amount: f32) -> Self {
+        self.modifiers.push(ValueModifier::Lighten(amount));
+        self
+    }
+
+    pub fn darken(mut self, amount: f32) -> Self {
+        self.modifiers.push(ValueModifier::Darken(amount));
+        self
+    }
+
+    pub fn alpha(mut self, alpha: f32) -> Self {
+        self.modifiers.push(ValueModifier::Alpha(alpha));
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.modifiers.push(ValueModifier::Scale(scale));
+        self
+    }
+
+    /// Computes the value of the reference from the variable it references.
+    pub fn resolve(&self, variable: &ThemeVariable) -> Option<ThemeVariable> {
+        self.modifiers
+            .iter()
+            .try_fold(variable.clone(), |value, modifier| value.modify(modifier))
+    }
+}
+
+pub(crate) type ResolvePropertyFn = fn(&ThemeVariable) -> Option<Box<dyn ThemeProperty>>;
+
+/// Returns the function used to create a `T` property from a variable.
+pub(crate) fn property_resolver<T>() -> ResolvePropertyFn
+where
+    T: 'static + ThemeProperty + ThemePropertyName,
+{
+    |variable| {
+        T::from_variable(variable).map(|property| Box::new(property) as Box<dyn ThemeProperty>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theming::{
+        BackgroundImageProperty, ColorProperty, FlexBasisProperty, FlexGrowProperty,
+        MarginProperty, SizeProperty,
+    };
+
+    const GRAY: ThemeVariable = ThemeVariable::Color(Color::rgb(0.5, 0.5, 0.5));
+
+    fn lightness(variable: Option<ThemeVariable>) -> f32 {
+        match variable.map(|variable| variable.convert::<Color>().unwrap().as_hsla()) {
+            Some(Color::Hsla { lightness, .. }) => lightness,
+            color => panic!("expected a colour, got {:?}", color),
+        }
+    }
+
+    fn resolve<T>(variable: &ThemeVariable) -> Option<T>
+    where
+        T: 'static + Clone + ThemeProperty + ThemePropertyName,
+    {
+        property_resolver::<T>()(variable)
+            .and_then(|property| property.as_any().downcast_ref::<T>().cloned())
+    }
+
+    #[test]
+    fn lighten_and_darken_change_the_lightness() {
+        let lighter = lightness(GRAY.modify(&ValueModifier::Lighten(0.2)));
+        assert!((lighter - 0.7).abs() < 1e-4);
+        let darker = lightness(GRAY.modify(&ValueModifier::Darken(0.2)));
+        assert!((darker - 0.3).abs() < 1e-4);
+        // The lightness stays between 0 and 1.
+        assert_eq!(lightness(GRAY.modify(&ValueModifier::Lighten(1.0))), 1.0);
+        assert_eq!(lightness(GRAY.modify(&ValueModifier::Darken(1.0))), 0.0);
+    }
+
+    #[test]
+    fn alpha_replaces_the_alpha() {
+        let color = GRAY.modify(&ValueModifier::Alpha(0.25));
+        assert_eq!(
+            color,
+            Some(ThemeVariable::Color(Color::rgba(0.5, 0.5, 0.5, 0.25)))
+        );
+    }
+
+    #[test]
+    fn scale_multiplies_numbers_and_vals() {
+        let scale = ValueModifier::Scale(2.0);
+        assert_eq!(
+            ThemeVariable::Number(1.5).modify(&scale),
+            Some(ThemeVariable::Number(3.0))
+        );
+        assert_eq!(
+            ThemeVariable::Val(Val::Px(4.0)).modify(&scale),
+            Some(ThemeVariable::Val(Val::Px(8.0)))
+        );
+        assert_eq!(
+            ThemeVariable::Val(Val::Percent(25.0)).modify(&scale),
+            Some(ThemeVariable::Val(Val::Percent(50.0)))
+        );
+        assert_eq!(ThemeVariable::Val(Val::Auto).modify(&scale), None);
+    }
+
+    #[test]
+    fn modifiers_of_other_kinds_of_variables_fail() {
+        assert_eq!(GRAY.modify(&ValueModifier::Scale(2.0)), None);
+        let number = ThemeVariable::Number(1.0);
+        assert_eq!(number.modify(&ValueModifier::Lighten(0.1)), None);
+        assert_eq!(number.modify(&ValueModifier::Alpha(0.5)), None);
+        let string = ThemeVariable::String("font.ttf".to_string());
+        assert_eq!(string.modify(&ValueModifier::Darken(0.1)), None);
+    }
+
+    #[test]
+    fn resolve_applies_modifiers_in_order() {
+        let reference = VariableRef::new("gray").lighten(0.2).alpha(0.5);
+        let resolved = reference.resolve(&GRAY);
+        assert!((lightness(resolved.clone()) - 0.7).abs() < 1e-4);
+        assert_eq!(resolved.unwrap().convert::<Color>().unwrap().a(), 0.5);
+
+        assert_eq!(VariableRef::new("gray").resolve(&GRAY), Some(GRAY));
+        let spacing = ThemeVariable::Number(4.0);
+        assert_eq!(
+            VariableRef::new("spacing")
+                .scale(2.0)
+                .scale(0.5)
+                .resolve(&spacing),
+            Some(spacing)
+        );
+    }
+
+    #[test]
+    fn resolve_fails_when_a_modifier_doesnt_apply() {
+        let reference = VariableRef::new("gray").scale(2.0).lighten(0.1);
+        assert_eq!(reference.resolve(&GRAY), None);
+    }
+
+    #[test]
+    fn property_resolver_converts_variables() {
+        assert_eq!(
+            resolve::<ColorProperty>(&GRAY).map(|property| property.0),
+            Some(Color::rgb(0.5, 0.5, 0.5))
+        );
+        assert_eq!(
+            resolve::<FlexGrowProperty>(&ThemeVariable::Number(2.0)).map(|property| property.0),
+            Some(2.0)
+        );
+        let path = ThemeVariable::String("bg.png".to_string());
+        assert_eq!(
+            resolve::<BackgroundImageProperty>(&path).map(|property| property.0),
+            Some("bg.png".to_string())
+        );
+        // Numbers are pixel values, and values are used for every side or dimension.
+        assert_eq!(
+            resolve::<FlexBasisProperty>(&ThemeVariable::Number(10.0)).map(|property| property.0),
+            Some(Val::Px(10.0))
+        );
+        assert_eq!(
+            resolve::<MarginProperty>(&ThemeVariable::Val(Val::Percent(5.0)))
+                .map(|property| property.0),
+            Some(UiRect::all(Val::Percent(5.0)))
+        );
+        assert_eq!(
+            resolve::<SizeProperty>(&ThemeVariable::Number(8.0)).map(|property| property.0),
+            Some(Size::new(Val::Px(8.0), Val::Px(8.0)))
+        );
+    }
+
+    #[test]
+    fn property_resolver_rejects_mismatched_types() {
+        assert!(resolve::<ColorProperty>(&ThemeVariable::Number(1.0)).is_none());
+        assert!(resolve::<FlexGrowProperty>(&GRAY).is_none());
+        assert!(resolve::<FlexGrowProperty>(&ThemeVariable::Val(Val::Px(1.0))).is_none());
+        assert!(resolve::<MarginProperty>(&ThemeVariable::String("1px".to_string())).is_none());
+        assert!(resolve::<BackgroundImageProperty>(&ThemeVariable::Number(1.0)).is_none());
+    }
+}
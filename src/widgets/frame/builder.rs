@@ -68,7 +68,6 @@ impl<'a, 'w, 's> FrameWidgetBuilder<'a, 'w, 's> {
             })),
             close_button: WidgetBuilderEntity::new(Some(ButtonBundle {
                 style: Style {
-                    //size: Size::new(Val::Px(35.0), Val::Px(35.0)),
                     aspect_ratio: Some(1.0),
                     ..default()
                 },
@@ -169,12 +168,14 @@ impl<'a, 'w, 's> FrameWidgetBuilder<'a, 'w, 's> {
         let title_text = commands
             .spawn_bundle(self.title_text.bundle.take().unwrap())
             .insert(RootEntity(root))
+            .insert(FrameTitleText)
             .run_entity_commands(&self.title_text.commands_runners)
             .id();
 
         let close_button = commands
             .spawn_bundle(self.close_button.bundle.take().unwrap())
             .insert(RootEntity(root))
+            .insert(FrameCloseButton)
             .run_entity_commands(&self.close_button.commands_runners)
             .id();
 
@@ -6,13 +6,20 @@ use bevy_utils::prelude::*;
 mod builder;
 pub use builder::*;
 
-use crate::components::grab::{Grab, Grabbed};
+use crate::{
+    components::grab::{Grab, Grabbed},
+    theming::ThemeMarkerAppExt,
+};
 
 pub struct FramePlugin;
 
 impl Plugin for FramePlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(move_frame);
+        app.register_theme_marker::<Frame>("Frame")
+            .register_theme_marker::<FrameGrabber>("FrameGrabber")
+            .register_theme_marker::<FrameTitleText>("FrameTitleText")
+            .register_theme_marker::<FrameCloseButton>("FrameCloseButton")
+            .add_system(move_frame);
     }
 }
 
@@ -29,6 +36,14 @@ pub struct Frame;
 #[derive(Component)]
 pub struct FrameGrabber;
 
+/// Marker component for the text of a frame's title bar.
+#[derive(Component)]
+pub struct FrameTitleText;
+
+/// Marker component for the close button of a frame's title bar.
+#[derive(Component)]
+pub struct FrameCloseButton;
+
 /// Describes a frame's root UI node.
 #[derive(Component)]
 pub struct RootEntity(pub Entity);
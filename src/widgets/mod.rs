@@ -1,3 +1,8 @@
 pub mod frame;
+pub mod scroll_view;
 pub mod slider;
+pub mod spin_box;
+pub mod text_area;
+pub mod text_input;
+pub mod theme_inspector;
 pub mod tooltip;
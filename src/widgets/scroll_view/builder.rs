@@ -0,0 +1,348 @@
+use bevy_ecs::{prelude::*, system::EntityCommands};
+use bevy_hierarchy::prelude::*;
+use bevy_render::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+use crate::{components::grab::Grab, utils::*};
+
+use super::*;
+
+/// Builds a scroll view, which clips its content and scrolls it with the mouse wheel and scrollbars.
+///
+/// The content node grows with its children, so horizontal scrolling only happens when they are
+/// wider than the viewport. Give the content a `min_size` to stretch it to the viewport instead.
+pub struct ScrollViewWidgetBuilder<'a, 'w, 's> {
+    root: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    viewport: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    content: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    horizontal_scrollbar: Option<ScrollbarWidgetBuilder<'a, 'w, 's>>,
+    vertical_scrollbar: Option<ScrollbarWidgetBuilder<'a, 'w, 's>>,
+    content_entities: Vec<Entity>,
+}
+
+struct ScrollbarWidgetBuilder<'a, 'w, 's> {
+    track: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    thumb: WidgetBuilderEntity<'a, 'w, 's, Option<ButtonBundle>>,
+}
+
+pub struct ScrollViewWidgetEntities {
+    pub root: Entity,
+    pub viewport: Entity,
+    pub content: Entity,
+    pub horizontal_scrollbar: Option<ScrollbarNodes>,
+    pub vertical_scrollbar: Option<ScrollbarNodes>,
+}
+
+impl Default for ScrollViewWidgetBuilder<'_, '_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'w, 's> ScrollViewWidgetBuilder<'a, 'w, 's> {
+    /// Creates a new scroll view builder, with a vertical scrollbar.
+    pub fn new() -> Self {
+        Self {
+            root: WidgetBuilderEntity::new(Some(NodeBundle {
+                color: Color::NONE.into(),
+                ..default()
+            })),
+            viewport: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::ColumnReverse,
+                    align_items: AlignItems::FlexStart,
+                    overflow: Overflow::Hidden,
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })),
+            content: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    flex_shrink: 0.0,
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })),
+            horizontal_scrollbar: None,
+            vertical_scrollbar: Some(ScrollbarWidgetBuilder::new(ScrollAxis::Vertical)),
+            content_entities: Vec::new(),
+        }
+    }
+
+    /// Allows to run commands on the root entity after it's spawned.
+    pub fn root_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.root.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the root bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn root_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.root.bundle = Some(extend(self.root.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the viewport entity after it's spawned.
+    pub fn viewport_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.viewport.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the viewport bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn viewport_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.viewport.bundle = Some(extend(self.viewport.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the content entity after it's spawned.
+    pub fn content_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.content.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the content bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn content_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.content.bundle = Some(extend(self.content.bundle.take().unwrap()));
+        self
+    }
+
+    /// Adds an entity to the scrolled content.
+    /// The entity should be a valid UI node and will be added to the content's children when spawn() is called.
+    pub fn with_content(&mut self, entity: Entity) -> &mut Self {
+        self.content_entities.push(entity);
+        self
+    }
+
+    /// Adds a scrollbar for the axis. Only the vertical scrollbar is enabled by default.
+    pub fn with_scrollbar(&mut self, axis: ScrollAxis) -> &mut Self {
+        self.scrollbar_mut(axis);
+        self
+    }
+
+    /// Removes the scrollbar of the axis. The view can still be scrolled with the mouse wheel.
+    pub fn without_scrollbar(&mut self, axis: ScrollAxis) -> &mut Self {
+        match axis {
+            ScrollAxis::Horizontal => self.horizontal_scrollbar = None,
+            ScrollAxis::Vertical => self.vertical_scrollbar = None,
+        }
+        self
+    }
+
+    /// Allows to run commands on the track entity of a scrollbar after it's spawned.
+    /// This enables the scrollbar if it wasn't already.
+    pub fn scrollbar_track_commands(
+        &mut self,
+        axis: ScrollAxis,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        let track = &mut self.scrollbar_mut(axis).track;
+        track.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the track bundle of a scrollbar before it is spawned.
+    /// This enables the scrollbar if it wasn't already.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn scrollbar_track_bundle(
+        &mut self,
+        axis: ScrollAxis,
+        extend: impl FnOnce(NodeBundle) -> NodeBundle,
+    ) -> &mut Self {
+        let track = &mut self.scrollbar_mut(axis).track;
+        track.bundle = Some(extend(track.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the thumb entity of a scrollbar after it's spawned.
+    /// This enables the scrollbar if it wasn't already.
+    pub fn scrollbar_thumb_commands(
+        &mut self,
+        axis: ScrollAxis,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        let thumb = &mut self.scrollbar_mut(axis).thumb;
+        thumb.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the thumb bundle of a scrollbar before it is spawned.
+    /// This enables the scrollbar if it wasn't already.
+    /// The thumb's length and position along the axis are set by the scroll view.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn scrollbar_thumb_bundle(
+        &mut self,
+        axis: ScrollAxis,
+        extend: impl FnOnce(ButtonBundle) -> ButtonBundle,
+    ) -> &mut Self {
+        let thumb = &mut self.scrollbar_mut(axis).thumb;
+        thumb.bundle = Some(extend(thumb.bundle.take().unwrap()));
+        self
+    }
+
+    /// Spawns the entity and returns the entities of the scroll view.
+    /// Using the builder again after calling this will panic.
+    pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> ScrollViewWidgetEntities {
+        let root = commands
+            .spawn_bundle(self.root.bundle.take().unwrap())
+            .insert(ScrollView::default())
+            .insert(ScrollPosition::default())
+            .run_entity_commands(&self.root.commands_runners)
+            .id();
+
+        let viewport = commands
+            .spawn_bundle(self.viewport.bundle.take().unwrap())
+            .insert(ScrollViewViewport(root))
+            .run_entity_commands(&self.viewport.commands_runners)
+            .id();
+
+        let content = commands
+            .spawn_bundle(self.content.bundle.take().unwrap())
+            .insert(ScrollViewContent(root))
+            .run_entity_commands(&self.content.commands_runners)
+            .push_children(&self.content_entities)
+            .id();
+
+        commands.entity(viewport).add_child(content);
+        commands.entity(root).add_child(viewport);
+
+        // Scrollbars are added after the viewport so that they're drawn over the content.
+        let horizontal_scrollbar = self
+            .horizontal_scrollbar
+            .as_mut()
+            .map(|scrollbar| scrollbar.spawn(commands, root, ScrollAxis::Horizontal));
+        let vertical_scrollbar = self
+            .vertical_scrollbar
+            .as_mut()
+            .map(|scrollbar| scrollbar.spawn(commands, root, ScrollAxis::Vertical));
+
+        commands.entity(root).insert(ScrollViewNodes {
+            viewport,
+            content,
+            horizontal_scrollbar,
+            vertical_scrollbar,
+        });
+
+        ScrollViewWidgetEntities {
+            root,
+            viewport,
+            content,
+            horizontal_scrollbar,
+            vertical_scrollbar,
+        }
+    }
+
+    fn scrollbar_mut(&mut self, axis: ScrollAxis) -> &mut ScrollbarWidgetBuilder<'a, 'w, 's> {
+        let scrollbar = match axis {
+            ScrollAxis::Horizontal => &mut self.horizontal_scrollbar,
+            ScrollAxis::Vertical => &mut self.vertical_scrollbar,
+        };
+        scrollbar.get_or_insert_with(|| ScrollbarWidgetBuilder::new(axis))
+    }
+}
+
+impl<'a, 'w, 's> ScrollbarWidgetBuilder<'a, 'w, 's> {
+    /// The track is stretched along the bottom or right edge of the scroll view, and the thumb
+    /// across the track. The track is 8 pixels thick, which themes can change with the `size`
+    /// property of the `HorizontalScrollbar` and `VerticalScrollbar` selectors.
+    fn new(axis: ScrollAxis) -> Self {
+        let (track_size, track_position, thumb_position) = match axis {
+            ScrollAxis::Horizontal => (
+                Size::new(Val::Auto, Val::Px(8.0)),
+                UiRect {
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+                UiRect {
+                    top: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+            ),
+            ScrollAxis::Vertical => (
+                Size::new(Val::Px(8.0), Val::Auto),
+                UiRect {
+                    top: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    ..default()
+                },
+                UiRect {
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    ..default()
+                },
+            ),
+        };
+        Self {
+            track: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: track_position,
+                    size: track_size,
+                    ..default()
+                },
+                color: Color::rgb(0.25, 0.25, 0.25).into(),
+                ..default()
+            })),
+            thumb: WidgetBuilderEntity::new(Some(ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: thumb_position,
+                    ..default()
+                },
+                color: Color::rgb(0.35, 0.55, 0.85).into(),
+                ..default()
+            })),
+        }
+    }
+
+    fn spawn(
+        &mut self,
+        commands: &mut Commands<'w, 's>,
+        root: Entity,
+        axis: ScrollAxis,
+    ) -> ScrollbarNodes {
+        let mut track = commands.spawn_bundle(self.track.bundle.take().unwrap());
+        track.insert(ScrollbarTrack {
+            scroll_view: root,
+            axis,
+        });
+        match axis {
+            ScrollAxis::Horizontal => track.insert(HorizontalScrollbar),
+            ScrollAxis::Vertical => track.insert(VerticalScrollbar),
+        };
+        let track = track.run_entity_commands(&self.track.commands_runners).id();
+
+        let thumb = commands
+            .spawn_bundle(self.thumb.bundle.take().unwrap())
+            .insert(ScrollbarThumb {
+                scroll_view: root,
+                axis,
+            })
+            .insert(Grab)
+            .run_entity_commands(&self.thumb.commands_runners)
+            .id();
+
+        commands.entity(track).add_child(thumb);
+        commands.entity(root).add_child(track);
+        ScrollbarNodes { track, thumb }
+    }
+}
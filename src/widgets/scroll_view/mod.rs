@@ -0,0 +1,528 @@
+use std::cmp::Ordering;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_input::{
+    mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use bevy_math::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_ui::{prelude::*, CalculatedClip, UiSystem};
+use bevy_window::prelude::*;
+
+mod builder;
+pub use builder::*;
+
+use crate::{components::grab::Grabbed, theming::ThemeMarkerAppExt, utils::*};
+
+/// Scrollbar thumbs never get shorter than this, so that they can still be grabbed when the
+/// content is much larger than the viewport.
+const MIN_THUMB_LENGTH: f32 = 20.0;
+
+pub struct ScrollViewPlugin;
+
+impl Plugin for ScrollViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScrollToEntity>()
+            .register_theme_marker::<ScrollView>("ScrollView")
+            .register_theme_marker::<ScrollViewViewport>("ScrollViewViewport")
+            .register_theme_marker::<ScrollViewContent>("ScrollViewContent")
+            .register_theme_marker::<ScrollbarTrack>("ScrollbarTrack")
+            .register_theme_marker::<ScrollbarThumb>("ScrollbarThumb")
+            .register_theme_marker::<HorizontalScrollbar>("HorizontalScrollbar")
+            .register_theme_marker::<VerticalScrollbar>("VerticalScrollbar")
+            .add_system(scroll_view_wheel)
+            .add_system(scrollbar_thumb_drag)
+            .add_system(scroll_to_entity)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                scroll_view_layout
+                    .label(ScrollViewSystem::Layout)
+                    .before(UiSystem::Flex),
+            );
+    }
+}
+
+/// Labels for the scroll view systems that run before the UI layout.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum ScrollViewSystem {
+    /// Clamps [`ScrollPosition`]s and moves the content and scrollbar thumbs accordingly.
+    Layout,
+}
+
+/// Added to the root of a scroll view.
+#[derive(Component, Clone, Debug)]
+pub struct ScrollView {
+    /// Distance scrolled for each line of a mouse wheel.
+    /// Touchpads report pixels, which are used as is.
+    pub line_height: f32,
+}
+
+impl Default for ScrollView {
+    fn default() -> Self {
+        Self { line_height: 20.0 }
+    }
+}
+
+/// Scroll offset of a scroll view's content, from its top left corner.
+///
+/// It can be written to scroll the view. The offset is clamped between zero and
+/// [`max_offset`](Self::max_offset) before the UI layout.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScrollPosition {
+    pub offset: Vec2,
+    max_offset: Vec2,
+}
+
+impl ScrollPosition {
+    pub fn new(offset: Vec2) -> Self {
+        Self {
+            offset,
+            max_offset: Vec2::ZERO,
+        }
+    }
+
+    /// The largest offset allowed by the size of the content during the last UI layout.
+    pub fn max_offset(&self) -> Vec2 {
+        self.max_offset
+    }
+
+    /// Clamps an offset between zero and [`max_offset`](Self::max_offset).
+    fn clamp(&self, offset: Vec2) -> Vec2 {
+        offset.clamp(Vec2::ZERO, self.max_offset)
+    }
+}
+
+/// Sent to scroll the nearest scroll view containing an entity until that entity is visible.
+///
+/// The view scrolls as little as needed, and aligns the entity's top left corner with the
+/// viewport's if it's larger than the viewport.
+pub struct ScrollToEntity(pub Entity);
+
+/// A direction in which a scroll view can scroll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl ScrollAxis {
+    fn of(&self, value: Vec2) -> f32 {
+        match self {
+            ScrollAxis::Horizontal => value.x,
+            ScrollAxis::Vertical => value.y,
+        }
+    }
+}
+
+/// Nodes of a scroll view, added to its root.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ScrollViewNodes {
+    /// The node clipping the content
+    pub viewport: Entity,
+    /// The node that is moved by scrolling, parent of the scrolled entities
+    pub content: Entity,
+    pub horizontal_scrollbar: Option<ScrollbarNodes>,
+    pub vertical_scrollbar: Option<ScrollbarNodes>,
+}
+
+impl ScrollViewNodes {
+    pub fn scrollbar(&self, axis: ScrollAxis) -> Option<ScrollbarNodes> {
+        match axis {
+            ScrollAxis::Horizontal => self.horizontal_scrollbar,
+            ScrollAxis::Vertical => self.vertical_scrollbar,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScrollbarNodes {
+    pub track: Entity,
+    pub thumb: Entity,
+}
+
+/// Marker component for the viewport of a scroll view.
+/// Also contains a reference to the scroll view's root entity.
+#[derive(Component)]
+pub struct ScrollViewViewport(pub Entity);
+
+/// Marker component for the content of a scroll view.
+/// Also contains a reference to the scroll view's root entity.
+#[derive(Component)]
+pub struct ScrollViewContent(pub Entity);
+
+/// Added to the track of a scrollbar, which is hidden when the content fits in the viewport.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ScrollbarTrack {
+    pub scroll_view: Entity,
+    pub axis: ScrollAxis,
+}
+
+/// Added to the thumb of a scrollbar, which can be grabbed to scroll.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ScrollbarThumb {
+    pub scroll_view: Entity,
+    pub axis: ScrollAxis,
+}
+
+/// Marker component for the track of a horizontal scrollbar.
+#[derive(Component)]
+pub struct HorizontalScrollbar;
+
+/// Marker component for the track of a vertical scrollbar.
+#[derive(Component)]
+pub struct VerticalScrollbar;
+
+/// Scrolls the topmost scroll view under the cursor with the mouse wheel or touchpad.
+/// Holding shift scrolls a mouse wheel horizontally.
+fn scroll_view_wheel(
+    mut wheel_events: EventReader<MouseWheel>,
+    keys: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    mut scroll_view_q: Query<(&ScrollView, &ScrollViewNodes, &mut ScrollPosition)>,
+    viewport_q: Query<(&Node, &GlobalTransform, Option<&CalculatedClip>)>,
+) {
+    let events: Vec<_> = wheel_events.iter().collect();
+    if events.is_empty() {
+        return;
+    }
+    let cursor_position = match windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+    {
+        Some(position) => position,
+        None => return,
+    };
+
+    let hovered = scroll_view_q
+        .iter()
+        .filter_map(|(_, nodes, _)| {
+            let (node, global_transform, clip) = viewport_q.get(nodes.viewport).ok()?;
+            let (min, max) = get_uinode_clipped_rect(global_transform, node, clip);
+            let contains = cursor_position.cmpge(min).all() && cursor_position.cmple(max).all();
+            contains.then(|| (nodes.viewport, global_transform.translation().z))
+        })
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .map(|(viewport, _)| viewport);
+
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    for (scroll_view, nodes, mut position) in scroll_view_q.iter_mut() {
+        if Some(nodes.viewport) != hovered {
+            continue;
+        }
+        let mut delta = Vec2::ZERO;
+        for event in &events {
+            let scale = match event.unit {
+                MouseScrollUnit::Line => scroll_view.line_height,
+                MouseScrollUnit::Pixel => 1.0,
+            };
+            delta += Vec2::new(event.x, event.y) * scale;
+        }
+        if shift && delta.x == 0.0 {
+            delta = Vec2::new(delta.y, 0.0);
+        }
+        // Scrolling up moves the content down, which reduces the offset.
+        let offset = position.clamp(position.offset + Vec2::new(-delta.x, -delta.y));
+        if position.offset != offset {
+            position.offset = offset;
+        }
+    }
+}
+
+fn scrollbar_thumb_drag(
+    thumb_q: Query<(&ScrollbarThumb, &Grabbed, &Node), Changed<Grabbed>>,
+    track_q: Query<&Node, With<ScrollbarTrack>>,
+    mut scroll_view_q: Query<(&ScrollViewNodes, &mut ScrollPosition)>,
+) {
+    for (thumb, grabbed, thumb_node) in thumb_q.iter() {
+        if let Ok((nodes, mut position)) = scroll_view_q.get_mut(thumb.scroll_view) {
+            let track_node = match nodes
+                .scrollbar(thumb.axis)
+                .and_then(|scrollbar| track_q.get(scrollbar.track).ok())
+            {
+                Some(node) => node,
+                None => continue,
+            };
+            let range = thumb.axis.of(track_node.size) - thumb.axis.of(thumb_node.size);
+            if range <= 0.0 {
+                continue;
+            }
+
+            let delta = grabbed.cursor_offset - grabbed.previous_cursor_offset;
+            let max_offset = position.max_offset;
+            let mut offset = position.offset;
+            match thumb.axis {
+                ScrollAxis::Horizontal => offset.x += delta.x * max_offset.x / range,
+                // The cursor's y axis points up, while the offset grows downwards.
+                ScrollAxis::Vertical => offset.y -= delta.y * max_offset.y / range,
+            }
+            let offset = position.clamp(offset);
+            if position.offset != offset {
+                position.offset = offset;
+            }
+        }
+    }
+}
+
+fn scroll_to_entity(
+    mut events: EventReader<ScrollToEntity>,
+    parent_q: Query<&Parent>,
+    content_q: Query<&ScrollViewContent>,
+    node_q: Query<(&Node, &GlobalTransform)>,
+    mut scroll_view_q: Query<(&ScrollViewNodes, &mut ScrollPosition)>,
+) {
+    for ScrollToEntity(target) in events.iter() {
+        let mut scroll_view = None;
+        let mut current = *target;
+        while let Ok(parent) = parent_q.get(current) {
+            current = parent.get();
+            if let Ok(content) = content_q.get(current) {
+                scroll_view = Some(content.0);
+                break;
+            }
+        }
+
+        let (nodes, mut position) = match scroll_view.and_then(|e| scroll_view_q.get_mut(e).ok()) {
+            Some(scroll_view) => scroll_view,
+            None => continue,
+        };
+        let rect = |entity| {
+            node_q.get(entity).ok().map(|(node, global_transform)| {
+                let center = global_transform.translation().truncate();
+                (center - node.size / 2.0, center + node.size / 2.0)
+            })
+        };
+        let (target, viewport) = match rect(*target).zip(rect(nodes.viewport)) {
+            Some(rects) => rects,
+            None => continue,
+        };
+
+        let offset = position.clamp(calculate_scroll_to(position.offset, target, viewport));
+        if position.offset != offset {
+            position.offset = offset;
+        }
+    }
+}
+
+/// Clamps the scroll position to the size of the content during the last layout, then moves the
+/// content and resizes the scrollbar thumbs.
+fn scroll_view_layout(
+    mut scroll_view_q: Query<(&ScrollViewNodes, &mut ScrollPosition)>,
+    node_q: Query<&Node>,
+    mut style_q: Query<&mut Style>,
+) {
+    for (nodes, mut position) in scroll_view_q.iter_mut() {
+        let (viewport_size, content_size) = match node_q
+            .get(nodes.viewport)
+            .ok()
+            .zip(node_q.get(nodes.content).ok())
+        {
+            Some((viewport, content)) => (viewport.size, content.size),
+            None => continue,
+        };
+
+        let max_offset = (content_size - viewport_size).max(Vec2::ZERO);
+        if position.max_offset != max_offset {
+            position.max_offset = max_offset;
+        }
+        let offset = position.clamp(position.offset);
+        if position.offset != offset {
+            position.offset = offset;
+        }
+
+        if let Ok(mut style) = style_q.get_mut(nodes.content) {
+            let content_position = UiRect {
+                left: Val::Px(-offset.x),
+                top: Val::Px(-offset.y),
+                ..style.position
+            };
+            if style.position != content_position {
+                style.position = content_position;
+            }
+        }
+
+        for axis in [ScrollAxis::Horizontal, ScrollAxis::Vertical] {
+            let scrollbar = match nodes.scrollbar(axis) {
+                Some(scrollbar) => scrollbar,
+                None => continue,
+            };
+            let scrollable = axis.of(max_offset) > 0.0;
+            if let Ok(mut style) = style_q.get_mut(scrollbar.track) {
+                let display = if scrollable {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+                if style.display != display {
+                    style.display = display;
+                }
+            }
+
+            let track_length = match node_q.get(scrollbar.track) {
+                Ok(node) if scrollable => axis.of(node.size),
+                _ => continue,
+            };
+            let (thumb_length, thumb_position) = calculate_thumb(
+                track_length,
+                axis.of(viewport_size),
+                axis.of(content_size),
+                axis.of(offset),
+                axis.of(max_offset),
+            );
+
+            if let Ok(mut style) = style_q.get_mut(scrollbar.thumb) {
+                let (mut size, mut thumb_rect) = (style.size, style.position);
+                match axis {
+                    ScrollAxis::Horizontal => {
+                        size.width = Val::Px(thumb_length);
+                        thumb_rect.left = Val::Px(thumb_position);
+                    }
+                    ScrollAxis::Vertical => {
+                        size.height = Val::Px(thumb_length);
+                        thumb_rect.top = Val::Px(thumb_position);
+                    }
+                }
+                if style.size != size {
+                    style.size = size;
+                }
+                if style.position != thumb_rect {
+                    style.position = thumb_rect;
+                }
+            }
+        }
+    }
+}
+
+/// Calculates the offset scrolling as little as needed for the target to be visible in the
+/// viewport, given the bottom-left and top-right corners of both.
+///
+/// Targets larger than the viewport are aligned with its top left corner.
+fn calculate_scroll_to(offset: Vec2, target: (Vec2, Vec2), viewport: (Vec2, Vec2)) -> Vec2 {
+    let ((target_min, target_max), (viewport_min, viewport_max)) = (target, viewport);
+    // Rects have their y axis pointing up, while the offset grows downwards.
+    let mut offset = offset;
+    if target_min.x < viewport_min.x {
+        offset.x -= viewport_min.x - target_min.x;
+    } else if target_max.x > viewport_max.x {
+        offset.x += f32::min(target_max.x - viewport_max.x, target_min.x - viewport_min.x);
+    }
+    if target_max.y > viewport_max.y {
+        offset.y -= target_max.y - viewport_max.y;
+    } else if target_min.y < viewport_min.y {
+        offset.y += f32::min(viewport_min.y - target_min.y, viewport_max.y - target_max.y);
+    }
+    offset
+}
+
+/// Calculates the length of a scrollbar thumb and its position from the start of the track, along
+/// the axis of the scrollbar.
+///
+/// The thumb is as much shorter than the track as the viewport is shorter than the content, but no
+/// shorter than [`MIN_THUMB_LENGTH`]. This expects the content to be scrollable, i.e. `max_offset`
+/// to be positive.
+fn calculate_thumb(
+    track_length: f32,
+    viewport_length: f32,
+    content_length: f32,
+    offset: f32,
+    max_offset: f32,
+) -> (f32, f32) {
+    let thumb_length = (track_length * viewport_length / content_length)
+        .clamp(MIN_THUMB_LENGTH.min(track_length), track_length);
+    let thumb_position = offset / max_offset * (track_length - thumb_length);
+    (thumb_length, thumb_position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A viewport of 100x50 pixels, with its bottom-left corner at the origin.
+    const VIEWPORT: (Vec2, Vec2) = (Vec2::ZERO, Vec2::new(100.0, 50.0));
+
+    fn position(offset: Vec2, max_offset: Vec2) -> ScrollPosition {
+        ScrollPosition { offset, max_offset }
+    }
+
+    #[test]
+    fn offsets_are_clamped_to_the_content() {
+        let position = position(Vec2::ZERO, Vec2::new(200.0, 100.0));
+        assert_eq!(position.clamp(Vec2::new(-10.0, 50.0)), Vec2::new(0.0, 50.0));
+        assert_eq!(
+            position.clamp(Vec2::new(250.0, 150.0)),
+            Vec2::new(200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn content_fitting_the_viewport_doesnt_scroll() {
+        let position = position(Vec2::ZERO, Vec2::ZERO);
+        assert_eq!(position.clamp(Vec2::new(30.0, -30.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn visible_targets_dont_scroll() {
+        let target = (Vec2::new(10.0, 10.0), Vec2::new(30.0, 30.0));
+        let offset = Vec2::new(5.0, 5.0);
+        assert_eq!(calculate_scroll_to(offset, target, VIEWPORT), offset);
+    }
+
+    #[test]
+    fn targets_after_the_viewport_scroll_forwards() {
+        // The target ends 20 pixels right of and 30 pixels below the viewport.
+        let target = (Vec2::new(110.0, -30.0), Vec2::new(120.0, -10.0));
+        assert_eq!(
+            calculate_scroll_to(Vec2::ZERO, target, VIEWPORT),
+            Vec2::new(20.0, 30.0)
+        );
+    }
+
+    #[test]
+    fn targets_before_the_viewport_scroll_backwards() {
+        // The target starts 20 pixels left of and 10 pixels above the viewport.
+        let target = (Vec2::new(-20.0, 50.0), Vec2::new(-10.0, 60.0));
+        assert_eq!(
+            calculate_scroll_to(Vec2::new(50.0, 50.0), target, VIEWPORT),
+            Vec2::new(30.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn large_targets_are_aligned_with_the_top_left_corner() {
+        // The target is larger than the viewport, and its top left corner is 110 pixels right of
+        // and 60 pixels below the viewport's.
+        let target = (Vec2::new(110.0, -110.0), Vec2::new(310.0, -10.0));
+        assert_eq!(
+            calculate_scroll_to(Vec2::ZERO, target, VIEWPORT),
+            Vec2::new(110.0, 60.0)
+        );
+    }
+
+    #[test]
+    fn thumbs_are_proportional_to_the_viewport() {
+        // The viewport shows a quarter of the content, scrolled to the middle.
+        assert_eq!(
+            calculate_thumb(100.0, 50.0, 200.0, 75.0, 150.0),
+            (25.0, 37.5)
+        );
+        assert_eq!(calculate_thumb(100.0, 50.0, 200.0, 0.0, 150.0), (25.0, 0.0));
+        assert_eq!(
+            calculate_thumb(100.0, 50.0, 200.0, 150.0, 150.0),
+            (25.0, 75.0)
+        );
+    }
+
+    #[test]
+    fn thumbs_have_a_minimum_length() {
+        let (length, position) = calculate_thumb(100.0, 50.0, 5000.0, 4950.0, 4950.0);
+        assert_eq!(length, MIN_THUMB_LENGTH);
+        assert_eq!(position, 100.0 - MIN_THUMB_LENGTH);
+        // Tracks shorter than the minimum length are filled by the thumb.
+        assert_eq!(
+            calculate_thumb(10.0, 50.0, 5000.0, 0.0, 4950.0),
+            (10.0, 0.0)
+        );
+    }
+}
@@ -13,12 +13,14 @@ pub struct SliderWidgetBuilder<'a, 'w, 's> {
     root: WidgetBuilderEntity<'a, 'w, 's, Option<SliderBundle>>,
     track: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
     thumb: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    value_label: Option<WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>>,
 }
 
 pub struct SliderWidgetEntities {
     pub root: Entity,
     pub track: Entity,
     pub thumb: Entity,
+    pub value_label: Option<Entity>,
 }
 
 impl Default for SliderWidgetBuilder<'_, '_, '_> {
@@ -64,6 +66,7 @@ impl<'a, 'w, 's> SliderWidgetBuilder<'a, 'w, 's> {
                 color: Color::rgb(0.25, 0.25, 0.25).into(),
                 ..default()
             })),
+            value_label: None,
         }
     }
 
@@ -115,6 +118,66 @@ impl<'a, 'w, 's> SliderWidgetBuilder<'a, 'w, 's> {
         self
     }
 
+    /// Adds a text node displaying the slider's value with the given style.
+    /// The value is formatted using the [`SliderValueFormatter`] of the root entity, if any.
+    ///
+    /// The label is positioned absolutely at the bottom left of the root node, so it doesn't change
+    /// the layout of the track. The root node should be tall enough for the label to fit below the
+    /// track, or the label can be moved with [`value_label_bundle`](Self::value_label_bundle).
+    pub fn with_value_label(&mut self, text_style: TextStyle) -> &mut Self {
+        if let Some(bundle) = self.value_label().bundle.as_mut() {
+            for section in &mut bundle.text.sections {
+                section.style = text_style.clone();
+            }
+        }
+        self
+    }
+
+    /// Returns the value label, which is added with the default text style if it wasn't already.
+    fn value_label(&mut self) -> &mut WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>> {
+        self.value_label.get_or_insert_with(|| {
+            WidgetBuilderEntity::new(Some(TextBundle {
+                text: Text::from_section("", default()),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(0.),
+                        bottom: Val::Px(0.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            }))
+        })
+    }
+
+    /// Allows you to run commands on the value label entity after it's spawned.
+    /// This enables the value label if it wasn't already, in which case its default text style has
+    /// no font and should be set for the value to be displayed.
+    pub fn value_label_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.value_label()
+            .commands_runners
+            .push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows you to edit the value label bundle before it is spawned.
+    /// This enables the value label if it wasn't already, in which case its default text style has
+    /// no font and should be set for the value to be displayed.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn value_label_bundle(
+        &mut self,
+        extend: impl FnOnce(TextBundle) -> TextBundle,
+    ) -> &mut Self {
+        let value_label = self.value_label();
+        value_label.bundle = Some(extend(value_label.bundle.take().unwrap()));
+        self
+    }
+
     /// Consumes the builder, spawns the entity and returns the EntityCommands for the root node.
     /// Calling this will consume the builder. If you don't call this, entities will still be
     /// created and destroyed
@@ -142,10 +205,24 @@ impl<'a, 'w, 's> SliderWidgetBuilder<'a, 'w, 's> {
 
         commands.entity(root).push_children(&[track, thumb]);
 
+        let value_label = self.value_label.as_mut().map(|value_label| {
+            commands
+                .spawn_bundle(value_label.bundle.take().unwrap())
+                .run_entity_commands(&value_label.commands_runners)
+                .insert(SliderValueLabelNode)
+                .insert(WidgetRoot(root))
+                .id()
+        });
+
+        if let Some(value_label) = value_label {
+            commands.entity(root).add_child(value_label);
+        }
+
         SliderWidgetEntities {
             root,
             track,
             thumb,
+            value_label,
         }
     }
 }
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+
+use super::Slider;
+
+/// Describes how a slider's value is converted to text.
+///
+/// When present on the slider's root entity, this is used by the slider tooltip and by the
+/// optional value label spawned by [`SliderWidgetBuilder`](super::SliderWidgetBuilder).
+/// When absent, the raw value is displayed.
+#[derive(Component, Clone)]
+pub enum SliderValueFormatter {
+    /// Displays `value * scale` with a fixed amount of decimal places, surrounded by a prefix and
+    /// a suffix (e.g. a unit).
+    Number {
+        scale: f32,
+        decimals: usize,
+        prefix: String,
+        suffix: String,
+    },
+    /// Displays the position of the value within the slider's range as a percentage.
+    Percent { decimals: usize },
+    /// Displays the label of the highest threshold that is lower or equal to the value.
+    /// Thresholds are expected to be sorted in ascending order.
+    Labels(Vec<(i32, String)>),
+    /// Formats the value using a custom function.
+    Custom(Arc<dyn Fn(&Slider) -> String + Send + Sync>),
+}
+
+impl Default for SliderValueFormatter {
+    fn default() -> Self {
+        Self::Number {
+            scale: 1.0,
+            decimals: 0,
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SliderValueFormatter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number {
+                scale,
+                decimals,
+                prefix,
+                suffix,
+            } => f
+                .debug_struct("Number")
+                .field("scale", scale)
+                .field("decimals", decimals)
+                .field("prefix", prefix)
+                .field("suffix", suffix)
+                .finish(),
+            Self::Percent { decimals } => f
+                .debug_struct("Percent")
+                .field("decimals", decimals)
+                .finish(),
+            Self::Labels(labels) => f.debug_tuple("Labels").field(labels).finish(),
+            Self::Custom(_) => f.debug_tuple("Custom").finish(),
+        }
+    }
+}
+
+impl SliderValueFormatter {
+    /// Displays the raw value followed by a unit, e.g. `"px"` or `" dB"`.
+    pub fn unit(suffix: impl Into<String>) -> Self {
+        Self::Number {
+            scale: 1.0,
+            decimals: 0,
+            prefix: String::new(),
+            suffix: suffix.into(),
+        }
+    }
+
+    /// Displays the value multiplied by `scale` with the given amount of decimal places.
+    ///
+    /// This is useful to display fractional values with an integer slider, e.g. a scale of `0.1`
+    /// and one decimal place will display a value of `15` as `1.5`.
+    pub fn decimals(scale: f32, decimals: usize) -> Self {
+        Self::Number {
+            scale,
+            decimals,
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// Maps value thresholds to labels. See [`SliderValueFormatter::Labels`].
+    pub fn labels<S: Into<String>>(labels: impl IntoIterator<Item = (i32, S)>) -> Self {
+        Self::Labels(
+            labels
+                .into_iter()
+                .map(|(threshold, label)| (threshold, label.into()))
+                .collect(),
+        )
+    }
+
+    /// Formats the value using a custom function.
+    pub fn custom(format: impl Fn(&Slider) -> String + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(format))
+    }
+
+    /// Returns the text for the current value of the slider.
+    pub fn format(&self, slider: &Slider) -> String {
+        match self {
+            Self::Number {
+                scale,
+                decimals,
+                prefix,
+                suffix,
+            } => format!(
+                "{}{:.*}{}",
+                prefix,
+                decimals,
+                slider.value as f32 * scale,
+                suffix
+            ),
+            Self::Percent { decimals } => {
+                let range = (slider.max - slider.min) as f32;
+                let percent = if range > 0.0 {
+                    (slider.value - slider.min) as f32 * 100.0 / range
+                } else {
+                    0.0
+                };
+                format!("{:.*}%", decimals, percent)
+            }
+            Self::Labels(labels) => labels
+                .iter()
+                .rev()
+                .find(|(threshold, _)| slider.value >= *threshold)
+                .or_else(|| labels.first())
+                .map(|(_, label)| label.clone())
+                .unwrap_or_default(),
+            Self::Custom(format) => format(slider),
+        }
+    }
+}
+
+/// Formats the value of a slider using its optional formatter.
+pub(crate) fn format_slider_value(
+    slider: &Slider,
+    formatter: Option<&SliderValueFormatter>,
+) -> String {
+    match formatter {
+        Some(formatter) => formatter.format(slider),
+        None => slider.value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slider(value: i32) -> Slider {
+        Slider {
+            value,
+            min: -50,
+            max: 150,
+            step: 1,
+        }
+    }
+
+    #[test]
+    fn number_scales_and_surrounds_the_value() {
+        let formatter = SliderValueFormatter::Number {
+            scale: 0.1,
+            decimals: 1,
+            prefix: "x".to_string(),
+            suffix: " m".to_string(),
+        };
+        assert_eq!(formatter.format(&slider(15)), "x1.5 m");
+        assert_eq!(SliderValueFormatter::unit("px").format(&slider(-3)), "-3px");
+        assert_eq!(SliderValueFormatter::default().format(&slider(7)), "7");
+    }
+
+    #[test]
+    fn percent_is_relative_to_the_range() {
+        let formatter = SliderValueFormatter::Percent { decimals: 0 };
+        assert_eq!(formatter.format(&slider(-50)), "0%");
+        assert_eq!(formatter.format(&slider(50)), "50%");
+        assert_eq!(formatter.format(&slider(150)), "100%");
+        let decimals = SliderValueFormatter::Percent { decimals: 1 };
+        assert_eq!(decimals.format(&slider(-49)), "0.5%");
+    }
+
+    #[test]
+    fn percent_of_an_empty_range_is_zero() {
+        let formatter = SliderValueFormatter::Percent { decimals: 0 };
+        let slider = Slider {
+            value: 10,
+            min: 10,
+            max: 10,
+            step: 1,
+        };
+        assert_eq!(formatter.format(&slider), "0%");
+    }
+
+    #[test]
+    fn labels_use_the_highest_threshold_reached() {
+        let formatter = SliderValueFormatter::labels([(0, "low"), (50, "mid"), (100, "high")]);
+        assert_eq!(formatter.format(&slider(0)), "low");
+        assert_eq!(formatter.format(&slider(49)), "low");
+        assert_eq!(formatter.format(&slider(50)), "mid");
+        assert_eq!(formatter.format(&slider(150)), "high");
+    }
+
+    #[test]
+    fn labels_below_the_first_threshold_use_the_first_label() {
+        let formatter = SliderValueFormatter::labels([(0, "low"), (50, "mid")]);
+        assert_eq!(formatter.format(&slider(-10)), "low");
+        assert_eq!(
+            SliderValueFormatter::Labels(Vec::new()).format(&slider(0)),
+            ""
+        );
+    }
+
+    #[test]
+    fn custom_and_missing_formatters() {
+        let formatter = SliderValueFormatter::custom(|slider| format!("<{}>", slider.value));
+        assert_eq!(formatter.format(&slider(3)), "<3>");
+        assert_eq!(format_slider_value(&slider(3), None), "3");
+        assert_eq!(format_slider_value(&slider(3), Some(&formatter)), "<3>");
+    }
+}
@@ -7,24 +7,32 @@ use bevy_ui::{prelude::*, FocusPolicy};
 use bevy_utils::prelude::*;
 
 mod builder;
+mod formatter;
 mod tooltip;
 
 pub use builder::*;
+pub use formatter::*;
 use tooltip::*;
 
-use crate::{components::grab::Grabbed, widgets::tooltip::*, utils::*};
+use crate::{components::grab::Grabbed, theming::ThemeMarkerAppExt, widgets::tooltip::*, utils::*};
 
 pub struct SliderPlugin;
 
 impl Plugin for SliderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(slider_test)
+        app.register_theme_marker::<Slider>("Slider")
+            .register_theme_marker::<SliderThumbNode>("SliderThumbNode")
+            .register_theme_marker::<SliderTrackNode>("SliderTrackNode")
+            .register_theme_marker::<SliderTooltipNode>("SliderTooltipNode")
+            .register_theme_marker::<SliderTooltipTextNode>("SliderTooltipTextNode")
+            .register_theme_marker::<SliderValueLabelNode>("SliderValueLabelNode")
             .add_system(slider_thumb_update)
             .add_system(slider_thumb_move)
             .add_system_to_stage(CoreStage::PreUpdate, slider_tooltip)
             .add_system(slider_tooltip_update)
             .add_system(slider_tooltip_text_update)
-            .add_system(slider_tooltip_visibility);
+            .add_system(slider_tooltip_visibility)
+            .add_system(slider_value_label_update);
     }
 }
 
@@ -52,6 +60,11 @@ impl Default for Slider {
 /// When present, describes the visual appearance for the slider tooltip.
 /// Remove this component to disable the slider's tooltip.
 ///
+/// The color and text style are applied when the tooltip is spawned and when this component
+/// changes. Like other widget colours, the color is overridden by themes styling the tooltip, e.g.
+/// the `Tooltip` selector of the built-in theme. Slider tooltips can be styled separately with the
+/// `SliderTooltipNode` selector.
+///
 /// TODO: Improve this. It's not ideal to have to copy all those fields here.
 #[derive(Component, Default, Clone)]
 pub struct SliderTooltip {
@@ -75,6 +88,10 @@ pub struct SliderTooltipNode;
 #[derive(Component)]
 pub struct SliderTooltipTextNode;
 
+/// Marker component for Slider's optional value label
+#[derive(Component)]
+pub struct SliderValueLabelNode;
+
 /// Marker component added to slider thumb while it's getting moved.
 #[derive(Component)]
 pub struct SliderThumbActive;
@@ -85,12 +102,6 @@ pub struct SliderThumbActive;
 #[derive(Component)]
 pub struct WidgetRoot(Entity);
 
-fn slider_test(query: Query<&Node, Added<SliderTooltip>>) {
-    for node in query.iter() {
-        println!("{:?}", node);
-    }
-}
-
 fn slider_thumb_update(
     mut thumb_q: Query<(&WidgetRoot, &Node, &mut Style), With<SliderThumbNode>>,
     track_q: Query<
@@ -171,6 +182,32 @@ fn slider_thumb_move(
     }
 }
 
+fn slider_value_label_update(
+    mut label_q: Query<
+        (&WidgetRoot, &mut Text, ChangeTrackers<SliderValueLabelNode>),
+        With<SliderValueLabelNode>,
+    >,
+    slider_q: Query<(
+        &Slider,
+        Option<&SliderValueFormatter>,
+        ChangeTrackers<Slider>,
+    )>,
+    formatter_q: Query<(), Changed<SliderValueFormatter>>,
+) {
+    for (root, mut text, label_tracker) in label_q.iter_mut() {
+        if let Ok((slider, formatter, slider_tracker)) = slider_q.get(root.0) {
+            if label_tracker.is_added()
+                || slider_tracker.is_changed()
+                || formatter_q.contains(root.0)
+            {
+                if let Some(section) = text.sections.first_mut() {
+                    section.value = format_slider_value(slider, formatter);
+                }
+            }
+        }
+    }
+}
+
 /// A UI node that is a slider
 #[derive(Bundle, Clone, Debug)]
 /// Describes the value properties of the slider
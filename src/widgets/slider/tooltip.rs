@@ -8,15 +8,26 @@ use super::*;
 // Adds and removes tooltip nodes.
 pub(crate) fn slider_tooltip(
     mut commands: Commands,
-    added_slider_q: Query<(Entity, &SliderTooltip), Added<SliderTooltip>>,
+    added_slider_q: Query<
+        (
+            Entity,
+            &SliderTooltip,
+            Option<&Slider>,
+            Option<&SliderValueFormatter>,
+        ),
+        Added<SliderTooltip>,
+    >,
     thumb_q: Query<(Entity, &WidgetRoot), With<SliderThumbNode>>,
     tooltip_q: Query<(Entity, &WidgetRoot)>,
     removed: RemovedComponents<SliderTooltip>,
 ) {
-    for (root, slider_tooltip) in added_slider_q.iter() {
+    for (root, slider_tooltip, slider, formatter) in added_slider_q.iter() {
+        let value = slider
+            .map(|slider| format_slider_value(slider, formatter))
+            .unwrap_or_default();
         let text = commands
             .spawn_bundle(TextBundle {
-                text: Text::from_section("0", slider_tooltip.text_style.clone()),
+                text: Text::from_section(value, slider_tooltip.text_style.clone()),
                 ..default()
             })
             .insert(SliderTooltipTextNode)
@@ -51,24 +62,21 @@ pub(crate) fn slider_tooltip(
     }
 }
 
-/// Updates the internal tooltip text style
+/// Updates the internal tooltip color and text style when the [`SliderTooltip`] changes.
 ///
-/// TODO: Fix the change detection query. Otherwise this copies the style object every frame.
+/// They are only written on changes, so that themes styling the tooltip aren't overwritten every
+/// frame.
 pub(crate) fn slider_tooltip_update(
-    slider_q: Query<&SliderTooltip /*, Or<(Changed<SliderTooltip>, Changed<Children>)>*/>,
-    mut tooltip_q: Query<(
-        &WidgetRoot,
-        &TooltipUiNodes,
-        &mut UiColor,
-    )>,
+    slider_q: Query<&SliderTooltip, Changed<SliderTooltip>>,
+    mut tooltip_q: Query<(&WidgetRoot, &TooltipUiNodes, &mut UiColor), With<SliderTooltipNode>>,
     mut tooltip_text_q: Query<&mut Text, With<TooltipTextUiNode>>,
 ) {
     for (root, nodes, mut color) in tooltip_q.iter_mut() {
-        if let Ok(mut text) = tooltip_text_q.get_mut(nodes.text) {
-            if let Ok(slider_tooltip) = slider_q.get(root.0) {
+        if let Ok(slider_tooltip) = slider_q.get(root.0) {
+            if let Ok(mut text) = tooltip_text_q.get_mut(nodes.text) {
                 text.sections[0].style = slider_tooltip.text_style.clone();
-                color.0 = slider_tooltip.color;
             }
+            color.0 = slider_tooltip.color;
         }
     }
 }
@@ -92,12 +100,17 @@ pub(crate) fn slider_tooltip_visibility(
 
 pub(crate) fn slider_tooltip_text_update(
     mut tooltip_text_q: Query<(&WidgetRoot, &mut Text), With<SliderTooltipTextNode>>,
-    slider_q: Query<&Slider, (With<SliderTooltip>, Changed<Slider>)>,
+    slider_q: Query<
+        (&Slider, Option<&SliderValueFormatter>),
+        (
+            With<SliderTooltip>,
+            Or<(Changed<Slider>, Changed<SliderValueFormatter>)>,
+        ),
+    >,
 ) {
     for (root, mut text) in tooltip_text_q.iter_mut() {
-        if let Ok(slider) = slider_q.get(root.0) {
-            println!("Changing text to {}", slider.value);
-            text.sections[0].value = slider.value.to_string();
+        if let Ok((slider, formatter)) = slider_q.get(root.0) {
+            text.sections[0].value = format_slider_value(slider, formatter);
         }
     }
 }
@@ -0,0 +1,354 @@
+use bevy_ecs::{prelude::*, system::EntityCommands};
+use bevy_hierarchy::prelude::*;
+use bevy_render::prelude::*;
+use bevy_text::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+use crate::{
+    components::{focus::Focusable, grab::Grab},
+    utils::*,
+    widgets::text_input::*,
+};
+
+use super::*;
+
+/// Builds a spin box: an optional label, a text input and buttons to decrement and increment the value.
+pub struct SpinBoxWidgetBuilder<'a, 'w, 's> {
+    root: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    label: Option<WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>>,
+    field: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    text: WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>,
+    decrement: WidgetBuilderEntity<'a, 'w, 's, Option<ButtonBundle>>,
+    increment: WidgetBuilderEntity<'a, 'w, 's, Option<ButtonBundle>>,
+    spin_box: SpinBox,
+    text_style: TextStyle,
+}
+
+pub struct SpinBoxWidgetEntities {
+    pub root: Entity,
+    pub label: Option<Entity>,
+    pub field: Entity,
+    pub text: Entity,
+    pub decrement: Entity,
+    pub increment: Entity,
+}
+
+impl Default for SpinBoxWidgetBuilder<'_, '_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'w, 's> SpinBoxWidgetBuilder<'a, 'w, 's> {
+    /// Creates a new spin box builder
+    pub fn new() -> Self {
+        let button = || {
+            WidgetBuilderEntity::new(Some(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(24.0), Val::Px(30.0)),
+                    margin: UiRect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: Color::rgb(0.25, 0.25, 0.25).into(),
+                ..default()
+            }))
+        };
+        Self {
+            root: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })),
+            label: None,
+            field: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    min_size: Size::new(Val::Px(60.0), Val::Px(30.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    align_items: AlignItems::Center,
+                    overflow: Overflow::Hidden,
+                    ..default()
+                },
+                color: Color::rgb(0.15, 0.15, 0.15).into(),
+                ..default()
+            })),
+            text: WidgetBuilderEntity::new(Some(TextBundle::default())),
+            decrement: button(),
+            increment: button(),
+            spin_box: SpinBox::default(),
+            text_style: TextStyle {
+                font_size: 16.0,
+                ..default()
+            },
+        }
+    }
+
+    /// Allows to run commands on the root entity after it's spawned.
+    pub fn root_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.root.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the root bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn root_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.root.bundle = Some(extend(self.root.bundle.take().unwrap()));
+        self
+    }
+
+    /// Adds a label before the field, which changes the value when dragged horizontally.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        let label = label.into();
+        self.label_bundle(|bundle| TextBundle {
+            text: Text::from_section(label, default()),
+            ..bundle
+        })
+    }
+
+    /// Allows to run commands on the label entity after it's spawned.
+    /// This enables the label if it wasn't already.
+    pub fn label_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.label_mut()
+            .commands_runners
+            .push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the label bundle before it is spawned.
+    /// This enables the label if it wasn't already.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn label_bundle(&mut self, extend: impl FnOnce(TextBundle) -> TextBundle) -> &mut Self {
+        let label = self.label_mut();
+        label.bundle = Some(extend(label.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the field entity after it's spawned.
+    pub fn field_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.field.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the field bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn field_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.field.bundle = Some(extend(self.field.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the text entity of the field after it's spawned.
+    pub fn text_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.text.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the text bundle of the field before it is spawned.
+    /// Its sections are replaced by the input's sections when spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn text_bundle(&mut self, extend: impl FnOnce(TextBundle) -> TextBundle) -> &mut Self {
+        self.text.bundle = Some(extend(self.text.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the decrement button after it's spawned.
+    pub fn decrement_button_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.decrement.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the decrement button bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn decrement_button_bundle(
+        &mut self,
+        extend: impl FnOnce(ButtonBundle) -> ButtonBundle,
+    ) -> &mut Self {
+        self.decrement.bundle = Some(extend(self.decrement.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the increment button after it's spawned.
+    pub fn increment_button_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.increment.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the increment button bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn increment_button_bundle(
+        &mut self,
+        extend: impl FnOnce(ButtonBundle) -> ButtonBundle,
+    ) -> &mut Self {
+        self.increment.bundle = Some(extend(self.increment.bundle.take().unwrap()));
+        self
+    }
+
+    /// Sets the style of the label, field and button texts.
+    /// The default style has no font, so this should be set for the texts to be displayed.
+    pub fn text_style(&mut self, text_style: TextStyle) -> &mut Self {
+        self.text_style = text_style;
+        self
+    }
+
+    /// Sets the initial value, which is clamped and snapped to a step when spawned.
+    pub fn value(&mut self, value: i32) -> &mut Self {
+        self.spin_box.value = value;
+        self
+    }
+
+    pub fn min(&mut self, min: i32) -> &mut Self {
+        self.spin_box.min = min;
+        self
+    }
+
+    pub fn max(&mut self, max: i32) -> &mut Self {
+        self.spin_box.max = max;
+        self
+    }
+
+    pub fn step(&mut self, step: i32) -> &mut Self {
+        self.spin_box.step = step;
+        self
+    }
+
+    /// Sets the distance the label is dragged for each step.
+    pub fn scrub_distance(&mut self, scrub_distance: f32) -> &mut Self {
+        self.spin_box.scrub_distance = scrub_distance;
+        self
+    }
+
+    /// Spawns the entity and returns the entities of the spin box.
+    /// Using the builder again after calling this will panic.
+    pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> SpinBoxWidgetEntities {
+        assert!(self.spin_box.step > 0 && self.spin_box.min <= self.spin_box.max);
+        let mut spin_box = self.spin_box.clone();
+        spin_box.value = spin_box.snap(spin_box.value);
+        let value = spin_box.value.to_string();
+
+        let root = commands
+            .spawn_bundle(self.root.bundle.take().unwrap())
+            .insert(spin_box)
+            .run_entity_commands(&self.root.commands_runners)
+            .id();
+
+        let text_style = self.text_style.clone();
+        let label = self.label.as_mut().map(|label| {
+            let mut bundle = label.bundle.take().unwrap();
+            for section in bundle.text.sections.iter_mut() {
+                section.style = text_style.clone();
+            }
+            commands
+                .spawn_bundle(bundle)
+                .insert(Interaction::default())
+                .insert(Grab)
+                .insert(SpinBoxLabel {
+                    spin_box: root,
+                    scrub_start: None,
+                })
+                .run_entity_commands(&label.commands_runners)
+                .id()
+        });
+
+        let text = spawn_text_input_text(commands, &mut self.text, &self.text_style);
+        let field = commands
+            .spawn_bundle(self.field.bundle.take().unwrap())
+            .insert(Interaction::default())
+            .insert(Focusable)
+            .insert(TextInput::new(value))
+            .insert(TextInputSettings {
+                filter: CharacterFilter::Custom(|c| c.is_ascii_digit() || c == '-'),
+                ..default()
+            })
+            .insert(TextInputNodes { text })
+            .insert(SpinBoxField(root))
+            .run_entity_commands(&self.field.commands_runners)
+            .add_child(text)
+            .id();
+        commands.entity(text).insert(TextInputText(field));
+
+        let decrement = spawn_button(commands, &mut self.decrement, &self.text_style, root, -1);
+        let increment = spawn_button(commands, &mut self.increment, &self.text_style, root, 1);
+
+        let mut children = Vec::from_iter(label);
+        children.extend([field, decrement, increment]);
+        commands
+            .entity(root)
+            .insert(SpinBoxNodes {
+                label,
+                field,
+                decrement,
+                increment,
+            })
+            .push_children(&children);
+
+        SpinBoxWidgetEntities {
+            root,
+            label,
+            field,
+            text,
+            decrement,
+            increment,
+        }
+    }
+
+    fn label_mut(&mut self) -> &mut WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>> {
+        self.label.get_or_insert_with(|| {
+            WidgetBuilderEntity::new(Some(TextBundle {
+                text: Text::from_section("", default()),
+                style: Style {
+                    margin: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                ..default()
+            }))
+        })
+    }
+}
+
+/// Spawns a button that steps the value of the spin box, labelled with the sign of the steps.
+fn spawn_button<'a, 'w, 's>(
+    commands: &mut Commands<'w, 's>,
+    button: &mut WidgetBuilderEntity<'a, 'w, 's, Option<ButtonBundle>>,
+    text_style: &TextStyle,
+    spin_box: Entity,
+    steps: i32,
+) -> Entity {
+    let label = if steps < 0 { "-" } else { "+" };
+    let text = commands
+        .spawn_bundle(TextBundle::from_section(label, text_style.clone()))
+        .insert(SpinBoxButtonText)
+        .id();
+    commands
+        .spawn_bundle(button.bundle.take().unwrap())
+        .insert(SpinBoxButton {
+            spin_box,
+            steps,
+            held: Duration::ZERO,
+        })
+        .run_entity_commands(&button.commands_runners)
+        .add_child(text)
+        .id()
+}
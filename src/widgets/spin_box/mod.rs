@@ -0,0 +1,293 @@
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_time::prelude::*;
+use bevy_ui::prelude::*;
+
+mod builder;
+pub use builder::*;
+
+use crate::{
+    components::{
+        grab::Grabbed,
+        state::{Disabled, Focused},
+    },
+    theming::ThemeMarkerAppExt,
+    widgets::text_input::{TextInput, TextInputSubmitted, TextInputSystem},
+};
+
+/// How long a button is held before it starts repeating.
+const REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// Time between repeated steps while a button is held.
+const REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct SpinBoxPlugin;
+
+impl Plugin for SpinBoxPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_theme_marker::<SpinBox>("SpinBox")
+            .register_theme_marker::<SpinBoxLabel>("SpinBoxLabel")
+            .register_theme_marker::<SpinBoxField>("SpinBoxField")
+            .register_theme_marker::<SpinBoxButton>("SpinBoxButton")
+            .register_theme_marker::<SpinBoxButtonText>("SpinBoxButtonText")
+            .add_system(spin_box_buttons)
+            .add_system(spin_box_scrub)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                spin_box_validate.before(TextInputSystem::Display),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                spin_box_field_update
+                    .after(spin_box_validate)
+                    .before(TextInputSystem::Display),
+            );
+    }
+}
+
+/// A value that can be typed, stepped with buttons or scrubbed by dragging the label.
+#[derive(Component, Clone, Debug)]
+pub struct SpinBox {
+    pub value: i32,
+    pub min: i32,
+    pub max: i32,
+    pub step: i32,
+    /// Distance the cursor is dragged on the label for each step.
+    pub scrub_distance: f32,
+}
+
+impl Default for SpinBox {
+    fn default() -> Self {
+        Self {
+            value: 0,
+            min: 0,
+            max: 100,
+            step: 1,
+            scrub_distance: 8.0,
+        }
+    }
+}
+
+impl SpinBox {
+    /// Clamps the value between `min` and `max`, and rounds it to the nearest step from `min`.
+    pub fn snap(&self, value: i32) -> i32 {
+        // Computed in i64 so that any i32 bounds and steps are exact and can't overflow.
+        let step = i64::from(self.step.max(1));
+        let min = i64::from(self.min);
+        let steps = (i64::from(value) - min + step / 2).div_euclid(step);
+        (min + steps * step).clamp(min, i64::from(self.max)) as i32
+    }
+
+    /// Adds a number of steps to the value, which may be negative.
+    pub fn step_by(&mut self, steps: i32) {
+        let value = self.snap(self.value.saturating_add(steps.saturating_mul(self.step)));
+        if self.value != value {
+            self.value = value;
+        }
+    }
+}
+
+/// The entities of a spin box, added to its root.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SpinBoxNodes {
+    pub label: Option<Entity>,
+    /// The text input displaying the value.
+    pub field: Entity,
+    pub decrement: Entity,
+    pub increment: Entity,
+}
+
+/// Added to the label of a spin box, which changes the value when dragged horizontally.
+#[derive(Component, Clone, Debug)]
+pub struct SpinBoxLabel {
+    pub spin_box: Entity,
+    /// Value of the spin box when the label was grabbed.
+    scrub_start: Option<i32>,
+}
+
+/// Added to the text input of a spin box.
+/// The typed value is validated when it's submitted or loses focus.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SpinBoxField(pub Entity);
+
+/// Added to the buttons of a spin box, which step the value while they are held.
+#[derive(Component, Clone, Debug)]
+pub struct SpinBoxButton {
+    pub spin_box: Entity,
+    /// Number of steps added to the value each time the button repeats, e.g. `-1` to decrement.
+    pub steps: i32,
+    /// Time since the button was pressed.
+    held: Duration,
+}
+
+/// Marker component for the text of a spin box button.
+#[derive(Component)]
+pub struct SpinBoxButtonText;
+
+/// Steps the value once when a button is pressed, then repeatedly while it is held.
+fn spin_box_buttons(
+    time: Res<Time>,
+    mut button_q: Query<(
+        &mut SpinBoxButton,
+        &Interaction,
+        ChangeTrackers<Interaction>,
+        Option<&Disabled>,
+    )>,
+    mut spin_box_q: Query<&mut SpinBox, Without<Disabled>>,
+) {
+    let repeats = |held: Duration| {
+        held.checked_sub(REPEAT_DELAY).map_or(0, |repeating| {
+            1 + (repeating.as_millis() / REPEAT_INTERVAL.as_millis()) as i32
+        })
+    };
+    for (mut button, interaction, interaction_tracker, disabled) in button_q.iter_mut() {
+        if *interaction != Interaction::Clicked || disabled.is_some() {
+            if button.held != Duration::ZERO {
+                button.held = Duration::ZERO;
+            }
+            continue;
+        }
+
+        let count = if interaction_tracker.is_changed() {
+            button.held = Duration::ZERO;
+            1
+        } else {
+            let held = button.held + time.delta();
+            let count = repeats(held) - repeats(button.held);
+            button.held = held;
+            count
+        };
+        if count > 0 {
+            if let Ok(mut spin_box) = spin_box_q.get_mut(button.spin_box) {
+                spin_box.step_by(button.steps * count);
+            }
+        }
+    }
+}
+
+/// Changes the value while the label is dragged, by one step every `scrub_distance`.
+fn spin_box_scrub(
+    mut label_q: Query<(&mut SpinBoxLabel, Option<&Grabbed>)>,
+    mut spin_box_q: Query<&mut SpinBox, Without<Disabled>>,
+) {
+    for (mut label, grabbed) in label_q.iter_mut() {
+        let grabbed = match grabbed {
+            Some(grabbed) => grabbed,
+            None => {
+                if label.scrub_start.is_some() {
+                    label.scrub_start = None;
+                }
+                continue;
+            }
+        };
+        if let Ok(mut spin_box) = spin_box_q.get_mut(label.spin_box) {
+            let start = *label.scrub_start.get_or_insert(spin_box.value);
+            let steps = (grabbed.cursor_offset.x / spin_box.scrub_distance.max(1.0)) as i32;
+            let value = spin_box.snap(start.saturating_add(steps.saturating_mul(spin_box.step)));
+            if spin_box.value != value {
+                spin_box.value = value;
+            }
+        }
+    }
+}
+
+/// Sets the value typed in the field when it's submitted or loses focus.
+/// Values that aren't numbers are reverted, and numbers are clamped and snapped to a step.
+fn spin_box_validate(
+    mut submitted_events: EventReader<TextInputSubmitted>,
+    removed_focused: RemovedComponents<Focused>,
+    mut field_q: Query<(&SpinBoxField, &mut TextInput)>,
+    mut spin_box_q: Query<&mut SpinBox>,
+) {
+    let fields = submitted_events
+        .iter()
+        .map(|event| event.entity)
+        .chain(removed_focused.iter());
+    for field in fields {
+        let (spin_box, mut input) = match field_q.get_mut(field) {
+            Ok(field) => field,
+            Err(_) => continue,
+        };
+        let mut spin_box = match spin_box_q.get_mut(spin_box.0) {
+            Ok(spin_box) => spin_box,
+            Err(_) => continue,
+        };
+
+        if let Ok(value) = input.value().trim().parse::<i32>() {
+            let value = spin_box.snap(value);
+            if spin_box.value != value {
+                spin_box.value = value;
+            }
+        }
+        let text = spin_box.value.to_string();
+        if input.value() != text {
+            input.set_value(text);
+        }
+    }
+}
+
+/// Displays the value in the field when it changes.
+fn spin_box_field_update(
+    spin_box_q: Query<(&SpinBox, &SpinBoxNodes, ChangeTrackers<SpinBox>)>,
+    mut input_q: Query<&mut TextInput>,
+) {
+    for (spin_box, nodes, tracker) in spin_box_q.iter() {
+        if !tracker.is_changed() {
+            continue;
+        }
+        if let Ok(mut input) = input_q.get_mut(nodes.field) {
+            // Keeps what is being typed, e.g. "05", as long as it has the same value.
+            if input.value().trim().parse::<i32>().ok() != Some(spin_box.value) {
+                input.set_value(spin_box.value.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_rounds_to_the_nearest_step_from_min() {
+        let spin_box = SpinBox {
+            min: -10,
+            max: 10,
+            step: 4,
+            ..Default::default()
+        };
+        assert_eq!(spin_box.snap(-9), -10);
+        assert_eq!(spin_box.snap(-8), -6);
+        assert_eq!(spin_box.snap(1), 2);
+        assert_eq!(spin_box.snap(9), 10);
+        assert_eq!(spin_box.snap(50), 10);
+        assert_eq!(spin_box.snap(-50), -10);
+    }
+
+    #[test]
+    fn snap_is_exact_for_large_values() {
+        let spin_box = SpinBox {
+            min: i32::MIN,
+            max: i32::MAX,
+            step: 1,
+            ..Default::default()
+        };
+        assert_eq!(spin_box.snap(16_777_217), 16_777_217);
+        assert_eq!(spin_box.snap(i32::MAX), i32::MAX);
+        assert_eq!(spin_box.snap(i32::MIN), i32::MIN);
+    }
+
+    #[test]
+    fn step_by_is_clamped() {
+        let mut spin_box = SpinBox {
+            value: 95,
+            step: 5,
+            ..Default::default()
+        };
+        spin_box.step_by(3);
+        assert_eq!(spin_box.value, 100);
+        spin_box.step_by(-30);
+        assert_eq!(spin_box.value, 0);
+    }
+}
@@ -0,0 +1,153 @@
+use bevy_ecs::{prelude::*, system::EntityCommands};
+use bevy_text::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+use crate::{
+    components::focus::Focusable,
+    utils::*,
+    widgets::{
+        scroll_view::{ScrollViewWidgetBuilder, ScrollViewWidgetEntities},
+        text_input::*,
+    },
+};
+
+use super::*;
+
+/// Builds a multi-line text input inside a scroll view.
+/// The text wraps at the width of the view, which scrolls to follow the caret.
+pub struct TextAreaWidgetBuilder<'a, 'w, 's> {
+    scroll_view: ScrollViewWidgetBuilder<'a, 'w, 's>,
+    text: WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>,
+    input: TextInput,
+    settings: TextInputSettings,
+    history: TextEditHistory,
+    text_style: TextStyle,
+}
+
+pub struct TextAreaWidgetEntities {
+    pub scroll_view: ScrollViewWidgetEntities,
+    pub text: Entity,
+}
+
+impl Default for TextAreaWidgetBuilder<'_, '_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'w, 's> TextAreaWidgetBuilder<'a, 'w, 's> {
+    /// Creates a new text area builder
+    pub fn new() -> Self {
+        let mut scroll_view = ScrollViewWidgetBuilder::new();
+        scroll_view.root_bundle(|bundle| NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(300.0), Val::Px(150.0)),
+                ..bundle.style
+            },
+            ..bundle
+        });
+        Self {
+            scroll_view,
+            text: WidgetBuilderEntity::new(Some(TextBundle::default())),
+            input: TextInput::default(),
+            settings: TextInputSettings {
+                multiline: true,
+                ..default()
+            },
+            history: TextEditHistory::default(),
+            text_style: TextStyle::default(),
+        }
+    }
+
+    /// Allows to edit the scroll view, whose root is also the root of the text area.
+    pub fn scroll_view(
+        &mut self,
+        edit: impl FnOnce(&mut ScrollViewWidgetBuilder<'a, 'w, 's>),
+    ) -> &mut Self {
+        edit(&mut self.scroll_view);
+        self
+    }
+
+    /// Allows to run commands on the text entity after it's spawned.
+    pub fn text_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.text.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the text bundle before it is spawned.
+    /// Its sections are replaced by the input's sections when spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn text_bundle(&mut self, extend: impl FnOnce(TextBundle) -> TextBundle) -> &mut Self {
+        self.text.bundle = Some(extend(self.text.bundle.take().unwrap()));
+        self
+    }
+
+    /// Sets the style of every section of the text.
+    /// The default style has no font, so this should be set for the text to be displayed.
+    pub fn text_style(&mut self, text_style: TextStyle) -> &mut Self {
+        self.text_style = text_style;
+        self
+    }
+
+    /// Sets the initial value.
+    pub fn value(&mut self, value: impl Into<String>) -> &mut Self {
+        self.input.set_value(value);
+        self
+    }
+
+    /// Sets the text displayed while the value is empty.
+    pub fn placeholder(&mut self, placeholder: impl Into<String>) -> &mut Self {
+        self.settings.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the maximum number of chars of the value, including line breaks.
+    pub fn max_length(&mut self, max_length: usize) -> &mut Self {
+        self.settings.max_length = Some(max_length);
+        self
+    }
+
+    /// Sets the chars that can be typed or pasted. Line breaks are always allowed.
+    pub fn filter(&mut self, filter: CharacterFilter) -> &mut Self {
+        self.settings.filter = filter;
+        self
+    }
+
+    /// Sets the number of edits that can be undone.
+    pub fn history_len(&mut self, max_len: usize) -> &mut Self {
+        self.history = TextEditHistory::new(max_len);
+        self
+    }
+
+    /// Spawns the entity and returns the entities of the text area.
+    /// Using the builder again after calling this will panic.
+    pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> TextAreaWidgetEntities {
+        let text = spawn_text_input_text(commands, &mut self.text, &self.text_style);
+
+        let input = self.input.clone();
+        let settings = self.settings.clone();
+        let history = self.history.clone();
+        let scroll_view = self
+            .scroll_view
+            .with_content(text)
+            .root_commands(move |commands| {
+                let root = commands.id();
+                commands
+                    .insert(TextArea::default())
+                    .insert(Interaction::default())
+                    .insert(Focusable)
+                    .insert(input.clone())
+                    .insert(settings.clone())
+                    .insert(history.clone())
+                    .insert(TextInputNodes { text });
+                commands.commands().entity(text).insert(TextInputText(root));
+            })
+            .spawn(commands);
+
+        TextAreaWidgetEntities { scroll_view, text }
+    }
+}
@@ -0,0 +1,124 @@
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_text::DefaultTextPipeline;
+use bevy_transform::prelude::*;
+use bevy_ui::{prelude::*, UiSystem};
+
+mod builder;
+pub use builder::*;
+
+use crate::{
+    theming::ThemeMarkerAppExt,
+    widgets::{
+        scroll_view::{ScrollPosition, ScrollViewNodes},
+        text_input::{TextInput, TextInputNodes, TextInputSystem, TEXT_INPUT_SECTIONS},
+    },
+};
+
+pub struct TextAreaPlugin;
+
+impl Plugin for TextAreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_theme_marker::<TextArea>("TextArea")
+            .add_system(text_area_follow_caret.after(TextInputSystem::Edit))
+            .add_system_to_stage(CoreStage::PostUpdate, text_area_wrap.before(UiSystem::Flex));
+    }
+}
+
+/// Added to the root of a text area, which is both a scroll view and a multi-line text input.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TextArea {
+    /// Set when the input changed, so that the view scrolls to the caret once the text is laid out.
+    follow_caret: bool,
+}
+
+/// Wraps the text at the width of the viewport, minus the vertical scrollbar that is drawn over it.
+fn text_area_wrap(
+    text_area_q: Query<(&ScrollViewNodes, &TextInputNodes), With<TextArea>>,
+    node_q: Query<&Node>,
+    mut style_q: Query<&mut Style>,
+) {
+    for (scroll_view_nodes, input_nodes) in text_area_q.iter() {
+        let viewport_width = match node_q.get(scroll_view_nodes.viewport) {
+            Ok(node) => node.size.x,
+            Err(_) => continue,
+        };
+        let scrollbar_width = scroll_view_nodes
+            .vertical_scrollbar
+            .and_then(|scrollbar| node_q.get(scrollbar.track).ok())
+            .map_or(0.0, |node| node.size.x);
+        let max_width = Val::Px((viewport_width - scrollbar_width).max(0.0));
+
+        if let Ok(mut style) = style_q.get_mut(input_nodes.text) {
+            if style.max_size.width != max_width {
+                style.max_size.width = max_width;
+            }
+        }
+    }
+}
+
+/// Scrolls text areas so that the caret stays in the viewport after each edit.
+/// The caret is found in the laid out glyphs, so this waits for the layout following the edit.
+fn text_area_follow_caret(
+    mut text_area_q: Query<(
+        &mut TextArea,
+        &mut ScrollPosition,
+        &ScrollViewNodes,
+        &TextInputNodes,
+        ChangeTrackers<TextInput>,
+    )>,
+    node_q: Query<(&Node, &GlobalTransform)>,
+    text_pipeline: Res<DefaultTextPipeline>,
+) {
+    for (mut text_area, mut position, scroll_view_nodes, input_nodes, input_tracker) in
+        text_area_q.iter_mut()
+    {
+        if input_tracker.is_changed() {
+            text_area.follow_caret = true;
+            continue;
+        }
+        if !text_area.follow_caret {
+            continue;
+        }
+        text_area.follow_caret = false;
+
+        let ((viewport, viewport_transform), (text, text_transform)) = match node_q
+            .get(scroll_view_nodes.viewport)
+            .ok()
+            .zip(node_q.get(input_nodes.text).ok())
+        {
+            Some(nodes) => nodes,
+            None => continue,
+        };
+        let layout = match text_pipeline.get_glyphs(&input_nodes.text) {
+            Some(layout) if layout.size.y > 0.0 => layout,
+            _ => continue,
+        };
+        // The caret is drawn by the sections tagged `caret`, which are empty when unfocused.
+        let caret = match layout
+            .glyphs
+            .iter()
+            .find(|glyph| TEXT_INPUT_SECTIONS.get(glyph.section_index) == Some(&"caret"))
+        {
+            Some(glyph) => glyph,
+            None => continue,
+        };
+
+        // Glyphs are laid out in physical pixels from the bottom of the text.
+        let scale = text.size.y / layout.size.y;
+        let text_bottom = text_transform.translation().y - text.size.y / 2.0;
+        let viewport_top = viewport_transform.translation().y + viewport.size.y / 2.0;
+        let caret_center = viewport_top - (text_bottom + caret.position.y * scale);
+        let caret_top = caret_center - caret.size.y * scale / 2.0;
+        let caret_bottom = caret_center + caret.size.y * scale / 2.0;
+
+        let offset = if caret_top < 0.0 {
+            position.offset.y + caret_top
+        } else if caret_bottom > viewport.size.y {
+            position.offset.y + caret_bottom - viewport.size.y
+        } else {
+            continue;
+        };
+        position.offset.y = offset.clamp(0.0, position.max_offset().y);
+    }
+}
@@ -0,0 +1,190 @@
+use bevy_ecs::{prelude::*, system::EntityCommands};
+use bevy_hierarchy::prelude::*;
+use bevy_render::prelude::*;
+use bevy_text::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+use crate::{components::focus::Focusable, theming::TextSectionTags, utils::*};
+
+use super::*;
+
+/// Builds a single-line text input, which is focused by clicking it.
+pub struct TextInputWidgetBuilder<'a, 'w, 's> {
+    root: WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>,
+    text: WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>,
+    input: TextInput,
+    settings: TextInputSettings,
+    text_style: TextStyle,
+}
+
+pub struct TextInputWidgetEntities {
+    pub root: Entity,
+    pub text: Entity,
+}
+
+impl Default for TextInputWidgetBuilder<'_, '_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'w, 's> TextInputWidgetBuilder<'a, 'w, 's> {
+    /// Creates a new text input builder
+    pub fn new() -> Self {
+        Self {
+            root: WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    min_size: Size::new(Val::Px(150.0), Val::Px(30.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    align_items: AlignItems::Center,
+                    overflow: Overflow::Hidden,
+                    ..default()
+                },
+                color: Color::rgb(0.15, 0.15, 0.15).into(),
+                ..default()
+            })),
+            text: WidgetBuilderEntity::new(Some(TextBundle::default())),
+            input: TextInput::default(),
+            settings: TextInputSettings::default(),
+            text_style: TextStyle {
+                font_size: 16.0,
+                ..default()
+            },
+        }
+    }
+
+    /// Allows to run commands on the root entity after it's spawned.
+    pub fn root_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.root.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the root bundle before it is spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn root_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.root.bundle = Some(extend(self.root.bundle.take().unwrap()));
+        self
+    }
+
+    /// Allows to run commands on the text entity after it's spawned.
+    pub fn text_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.text.commands_runners.push(Box::new(run_commands));
+        self
+    }
+
+    /// Allows to edit the text bundle before it is spawned.
+    /// Its sections are replaced by the input's sections when spawned.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn text_bundle(&mut self, extend: impl FnOnce(TextBundle) -> TextBundle) -> &mut Self {
+        self.text.bundle = Some(extend(self.text.bundle.take().unwrap()));
+        self
+    }
+
+    /// Sets the style of every section of the text.
+    /// The default style has no font, so this should be set for the text to be displayed.
+    /// The selection, caret and placeholder are drawn in other colours, which are overridden by the
+    /// built-in theme when it is active, e.g. with the `TextInputText::selection` selector.
+    pub fn text_style(&mut self, text_style: TextStyle) -> &mut Self {
+        self.text_style = text_style;
+        self
+    }
+
+    /// Sets the initial value.
+    pub fn value(&mut self, value: impl Into<String>) -> &mut Self {
+        self.input.set_value(value);
+        self
+    }
+
+    /// Sets the text displayed while the value is empty.
+    pub fn placeholder(&mut self, placeholder: impl Into<String>) -> &mut Self {
+        self.settings.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the maximum number of chars of the value.
+    pub fn max_length(&mut self, max_length: usize) -> &mut Self {
+        self.settings.max_length = Some(max_length);
+        self
+    }
+
+    /// Displays every char of the value as the mask, e.g. for passwords.
+    pub fn mask(&mut self, mask: char) -> &mut Self {
+        self.settings.mask = Some(mask);
+        self
+    }
+
+    /// Sets the chars that can be typed or pasted.
+    pub fn filter(&mut self, filter: CharacterFilter) -> &mut Self {
+        self.settings.filter = filter;
+        self
+    }
+
+    /// Spawns the entity and returns the entities of the text input.
+    /// Using the builder again after calling this will panic.
+    pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> TextInputWidgetEntities {
+        let root = commands
+            .spawn_bundle(self.root.bundle.take().unwrap())
+            .insert(Interaction::default())
+            .insert(Focusable)
+            .insert(self.input.clone())
+            .insert(self.settings.clone())
+            .run_entity_commands(&self.root.commands_runners)
+            .id();
+
+        let text = spawn_text_input_text(commands, &mut self.text, &self.text_style);
+        commands.entity(text).insert(TextInputText(root));
+
+        commands
+            .entity(root)
+            .insert(TextInputNodes { text })
+            .add_child(text);
+
+        TextInputWidgetEntities { root, text }
+    }
+}
+
+/// Spawns the text node of a text input, with a tagged section for each part of the input.
+/// The [`TextInputText`] component should be inserted once the root is spawned.
+///
+/// Sections use the text style, except the caret and selection which are drawn with an accent
+/// colour, and the placeholder which is drawn with a transparent text colour.
+pub(crate) fn spawn_text_input_text<'a, 'w, 's>(
+    commands: &mut Commands<'w, 's>,
+    text: &mut WidgetBuilderEntity<'a, 'w, 's, Option<TextBundle>>,
+    text_style: &TextStyle,
+) -> Entity {
+    let tags = TEXT_INPUT_SECTIONS
+        .iter()
+        .enumerate()
+        .fold(TextSectionTags::default(), |tags, (index, tag)| {
+            tags.with_tag(index, *tag)
+        });
+    let mut bundle = text.bundle.take().unwrap();
+    bundle.text.sections = TEXT_INPUT_SECTIONS
+        .iter()
+        .map(|tag| {
+            let mut style = text_style.clone();
+            match *tag {
+                "caret" | "selection" => style.color = Color::rgb(0.35, 0.55, 0.85),
+                "placeholder" => {
+                    style.color.set_a(text_style.color.a() * 0.5);
+                }
+                _ => {}
+            }
+            TextSection::new("", style)
+        })
+        .collect();
+    commands
+        .spawn_bundle(bundle)
+        .insert(tags)
+        .run_entity_commands(&text.commands_runners)
+        .id()
+}
@@ -0,0 +1,80 @@
+use bevy_ecs::prelude::*;
+
+use super::TextInput;
+
+/// Undo and redo history of a text input, edited with control and Z, Y or shift Z.
+///
+/// Consecutive typed chars are undone together. The history is added by the
+/// [`TextAreaWidgetBuilder`](crate::widgets::text_area::TextAreaWidgetBuilder), and can be
+/// inserted on single-line text inputs too.
+#[derive(Component, Clone, Debug)]
+pub struct TextEditHistory {
+    undo: Vec<TextInput>,
+    redo: Vec<TextInput>,
+    max_len: usize,
+    /// Whether the last recorded edit was typing, which the next typed chars are merged with.
+    typing: bool,
+}
+
+impl Default for TextEditHistory {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl TextEditHistory {
+    /// Creates a history that keeps up to `max_len` edits.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            max_len,
+            typing: false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+    }
+
+    /// Records the state of the input before an edit.
+    pub(crate) fn record(&mut self, before: &TextInput, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push(before.clone());
+            if self.undo.len() > self.max_len {
+                self.undo.remove(0);
+            }
+        }
+        self.typing = typing;
+        self.redo.clear();
+    }
+
+    /// Stops merging typed chars with the last edit, e.g. when the caret moves.
+    pub(crate) fn interrupt(&mut self) {
+        self.typing = false;
+    }
+
+    pub(crate) fn undo(&mut self, current: &TextInput) -> Option<TextInput> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.clone());
+        self.typing = false;
+        Some(previous)
+    }
+
+    pub(crate) fn redo(&mut self, current: &TextInput) -> Option<TextInput> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.clone());
+        self.typing = false;
+        Some(next)
+    }
+}
@@ -0,0 +1,884 @@
+use std::ops::Range;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_input::{keyboard::KeyboardInput, prelude::*, ButtonState};
+use bevy_text::{prelude::*, DefaultTextPipeline, TextLayoutInfo};
+use bevy_ui::UiSystem;
+use bevy_window::ReceivedCharacter;
+
+mod builder;
+mod history;
+pub use builder::*;
+pub use history::*;
+
+use crate::{
+    components::state::{Disabled, Focused},
+    theming::ThemeMarkerAppExt,
+};
+
+pub struct TextInputPlugin;
+
+impl Plugin for TextInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TextInputChanged>()
+            .add_event::<TextInputSubmitted>()
+            .init_resource::<TextInputClipboard>()
+            .register_theme_marker::<TextInput>("TextInput")
+            .register_theme_marker::<TextInputText>("TextInputText")
+            .add_system(text_input_keyboard.label(TextInputSystem::Edit))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                text_input_display
+                    .label(TextInputSystem::Display)
+                    .before(UiSystem::Flex),
+            );
+    }
+}
+
+/// Labels for the text input systems.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum TextInputSystem {
+    /// Edits focused text inputs from keyboard events.
+    Edit,
+    /// Updates the text nodes of text inputs, before the UI layout.
+    Display,
+}
+
+/// The value of a text input, along with its caret and selection.
+///
+/// Positions are counted in chars rather than bytes. The selection goes from an anchor to the caret,
+/// and is empty when both are at the same position.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    caret: usize,
+    anchor: usize,
+}
+
+impl TextInput {
+    /// Creates a text input with the caret after the value.
+    pub fn new(value: impl Into<String>) -> Self {
+        let mut input = Self::default();
+        input.set_value(value);
+        input
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the value and moves the caret after it.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.caret = self.len();
+        self.anchor = self.caret;
+    }
+
+    /// Position of the caret.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Range of the selected chars, which is empty when nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    pub fn selected_text(&self) -> &str {
+        let selection = self.selection();
+        &self.value[self.byte_index(selection.start)..self.byte_index(selection.end)]
+    }
+
+    /// Selects a range of chars, clamped to the value, and moves the caret to its end.
+    pub fn select(&mut self, range: Range<usize>) {
+        let len = self.len();
+        self.anchor = range.start.min(len);
+        self.caret = range.end.min(len);
+    }
+
+    pub fn select_all(&mut self) {
+        self.select(0..self.len());
+    }
+
+    /// Number of chars of the value.
+    pub fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    /// Moves the caret, extending the selection or collapsing it.
+    fn move_caret(&mut self, position: usize, select: bool) {
+        self.caret = position.min(self.len());
+        if !select {
+            self.anchor = self.caret;
+        }
+    }
+
+    /// Replaces the selection with the text, keeping only the chars accepted by the settings.
+    fn insert(&mut self, text: &str, settings: &TextInputSettings) {
+        let selection = self.selection();
+        let available = settings.max_length.map_or(usize::MAX, |max| {
+            max.saturating_sub(self.len() - selection.len())
+        });
+        let accepted: String = text
+            .chars()
+            .filter(|c| match c {
+                '\n' => settings.multiline,
+                c => !c.is_control() && settings.filter.accepts(*c),
+            })
+            .take(available)
+            .collect();
+        // Rejected chars leave the selection in place, while inserting nothing deletes it.
+        if accepted.is_empty() && (selection.is_empty() || !text.is_empty()) {
+            return;
+        }
+        let text = accepted;
+        let bytes = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.value.replace_range(bytes, &text);
+        self.move_caret(selection.start + text.chars().count(), false);
+    }
+
+    /// The chars in the range as they are displayed, replaced by the mask if any.
+    fn display(&self, range: Range<usize>, mask: Option<char>) -> String {
+        match mask {
+            Some(mask) => std::iter::repeat(mask).take(range.len()).collect(),
+            None => self
+                .value
+                .chars()
+                .skip(range.start)
+                .take(range.len())
+                .collect(),
+        }
+    }
+
+    fn delete_selection(&mut self) {
+        let selection = self.selection();
+        let bytes = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.value.replace_range(bytes, "");
+        self.move_caret(selection.start, false);
+    }
+
+    /// Lines of the value as ranges of chars, excluding line breaks.
+    ///
+    /// Lines are also split where the text wraps, given the vertical extent of the laid out glyph
+    /// of each char. Chars without glyphs, like spaces, stay on the line of the previous char.
+    fn rows(&self, glyph_extents: &[Option<(f32, f32)>]) -> Vec<Range<usize>> {
+        let mut rows = Vec::new();
+        let mut start = 0;
+        let mut extent: Option<(f32, f32)> = None;
+        for (index, c) in self.value.chars().enumerate() {
+            if c == '\n' {
+                rows.push(start..index);
+                start = index + 1;
+                extent = None;
+                continue;
+            }
+            if let Some(&Some((min, max))) = glyph_extents.get(index) {
+                let center = (min + max) / 2.0;
+                extent = match extent {
+                    Some((row_min, row_max)) if center < row_min || center > row_max => {
+                        rows.push(start..index);
+                        start = index;
+                        Some((min, max))
+                    }
+                    Some((row_min, row_max)) => Some((row_min.min(min), row_max.max(max))),
+                    None => Some((min, max)),
+                };
+            }
+        }
+        rows.push(start..self.len());
+        rows
+    }
+
+    /// Index of the row containing the position.
+    /// A position where a line wraps is at the start of the next row.
+    fn row_index(rows: &[Range<usize>], position: usize) -> usize {
+        rows.iter()
+            .rposition(|row| row.start <= position)
+            .unwrap_or(0)
+    }
+
+    /// Last position of the caret on a row, which is before the last char of a wrapped row since
+    /// the end of that row is the start of the next one.
+    fn row_end(rows: &[Range<usize>], index: usize) -> usize {
+        let row = &rows[index];
+        match rows.get(index + 1) {
+            Some(next) if next.start == row.end && row.end > row.start => row.end - 1,
+            _ => row.end,
+        }
+    }
+
+    /// Start of the row containing the position.
+    fn line_start(&self, position: usize, rows: &[Range<usize>]) -> usize {
+        rows[Self::row_index(rows, position)].start
+    }
+
+    /// End of the row containing the position.
+    fn line_end(&self, position: usize, rows: &[Range<usize>]) -> usize {
+        Self::row_end(rows, Self::row_index(rows, position))
+    }
+
+    /// Position in the previous or next row at the same column, or at the end of that row
+    /// if it's shorter.
+    fn vertical(&self, position: usize, down: bool, rows: &[Range<usize>]) -> usize {
+        let index = Self::row_index(rows, position);
+        let column = position - rows[index].start;
+        let target = if down {
+            index + 1
+        } else if index > 0 {
+            index - 1
+        } else {
+            return 0;
+        };
+        match rows.get(target) {
+            Some(row) => (row.start + column).min(Self::row_end(rows, target)),
+            None => self.len(),
+        }
+    }
+
+    /// Start of the word before the position, skipping whitespace.
+    fn previous_word(&self, position: usize) -> usize {
+        let chars: Vec<_> = self.value.chars().take(position).collect();
+        let mut index = chars.len();
+        while index > 0 && chars[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        while index > 0 && !chars[index - 1].is_whitespace() {
+            index -= 1;
+        }
+        index
+    }
+
+    /// End of the word after the position, skipping whitespace.
+    fn next_word(&self, position: usize) -> usize {
+        let chars: Vec<_> = self.value.chars().collect();
+        let mut index = position;
+        while index < chars.len() && chars[index].is_whitespace() {
+            index += 1;
+        }
+        while index < chars.len() && !chars[index].is_whitespace() {
+            index += 1;
+        }
+        index
+    }
+}
+
+/// Settings of a text input.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TextInputSettings {
+    /// Text displayed while the value is empty.
+    pub placeholder: String,
+    /// Maximum number of chars of the value.
+    pub max_length: Option<usize>,
+    /// When set, every char of the value is displayed as this char, and the value can't be copied.
+    pub mask: Option<char>,
+    /// Chars that can be typed or pasted. Line breaks of multiline inputs are always accepted.
+    pub filter: CharacterFilter,
+    /// Whether enter inserts line breaks, in which case control and enter submits the value.
+    pub multiline: bool,
+}
+
+/// Chars accepted by a text input.
+#[derive(Clone, Copy, Debug)]
+pub enum CharacterFilter {
+    Any,
+    /// ASCII digits only.
+    Digits,
+    /// Letters and digits.
+    Alphanumeric,
+    Custom(fn(char) -> bool),
+}
+
+impl Default for CharacterFilter {
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+impl CharacterFilter {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            CharacterFilter::Any => true,
+            CharacterFilter::Digits => c.is_ascii_digit(),
+            CharacterFilter::Alphanumeric => c.is_alphanumeric(),
+            CharacterFilter::Custom(accepts) => accepts(c),
+        }
+    }
+}
+
+/// Text copied or cut from text inputs, shared by the whole app.
+/// This doesn't use the clipboard of the operating system.
+#[derive(Default)]
+pub struct TextInputClipboard(pub String);
+
+/// Sent when the value of a text input is edited by the user.
+pub struct TextInputChanged {
+    pub entity: Entity,
+    pub value: String,
+}
+
+/// Sent when enter is pressed while a text input is focused.
+pub struct TextInputSubmitted {
+    pub entity: Entity,
+    pub value: String,
+}
+
+/// Nodes of a text input, added to its root.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct TextInputNodes {
+    pub text: Entity,
+}
+
+/// Marker component for the text node of a text input.
+/// Also contains a reference to the text input's root entity.
+///
+/// The text has a section for each part of the input, tagged so that themes can target them with
+/// `"TextInputText::text"`, `"TextInputText::selection"`, `"TextInputText::caret"` and
+/// `"TextInputText::placeholder"`.
+#[derive(Component)]
+pub struct TextInputText(pub Entity);
+
+/// Tags of the sections of a [`TextInputText`], in order.
+pub(crate) const TEXT_INPUT_SECTIONS: [&str; 6] =
+    ["text", "caret", "selection", "caret", "text", "placeholder"];
+
+/// Edits the focused text inputs with the keyboard.
+///
+/// Arrows move the caret by char, or by word while control is held, and shift extends the selection.
+/// Control with A, C, X or V selects all, copies, cuts or pastes using the [`TextInputClipboard`].
+/// Multiline inputs move between the displayed lines, including wrapped ones, with the up and down
+/// arrows, home and end, and insert a line break when enter is pressed without control.
+fn text_input_keyboard(
+    mut commands: Commands,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut char_events: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut clipboard: ResMut<TextInputClipboard>,
+    mut input_q: Query<
+        (
+            Entity,
+            &mut TextInput,
+            &TextInputSettings,
+            Option<&TextInputNodes>,
+            Option<&mut TextEditHistory>,
+        ),
+        (With<Focused>, Without<Disabled>),
+    >,
+    text_q: Query<&Text>,
+    text_pipeline: Res<DefaultTextPipeline>,
+    mut changed_events: EventWriter<TextInputChanged>,
+    mut submitted_events: EventWriter<TextInputSubmitted>,
+) {
+    // Key repeats are only reported as events.
+    let pressed: Vec<_> = keyboard_events
+        .iter()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .filter_map(|event| event.key_code)
+        .collect();
+    // Line breaks are inserted by the enter key, and other control chars aren't typed.
+    let typed_chars: Vec<_> = char_events
+        .iter()
+        .map(|event| event.char)
+        .filter(|c| !c.is_control())
+        .collect();
+    if pressed.is_empty() && typed_chars.is_empty() {
+        return;
+    }
+    let control = keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    // AltGr is reported as control and alt on some platforms, and types chars rather than commands.
+    let alt = keys.any_pressed([KeyCode::LAlt, KeyCode::RAlt]);
+    let command = control && !alt;
+
+    for (entity, mut input, settings, nodes, mut history) in input_q.iter_mut() {
+        let mut edited = input.clone();
+        let mut submitted = false;
+        // The text was laid out for the value before this frame's edits.
+        let laid_out_rows = settings.multiline.then(|| {
+            let extents = nodes
+                .and_then(|nodes| {
+                    let text = text_q.get(nodes.text).ok()?;
+                    let layout = text_pipeline.get_glyphs(&nodes.text)?;
+                    Some(glyph_extents(&input, text, layout))
+                })
+                .unwrap_or_default();
+            input.rows(&extents)
+        });
+        // Chars aren't ordered with key presses, so each pressed key that isn't a command is
+        // assumed to have typed the next char.
+        let mut typed = typed_chars.iter().filter(|_| !command);
+        for key in &pressed {
+            let before = edited.clone();
+            let mut typing = false;
+            let rows = match &laid_out_rows {
+                Some(rows) if edited.value == input.value => rows.clone(),
+                _ => edited.rows(&[]),
+            };
+            match key {
+                KeyCode::Z | KeyCode::Y if command => {
+                    let redo = *key == KeyCode::Y || shift;
+                    let state = history.as_mut().and_then(|history| {
+                        if redo {
+                            history.redo(&edited)
+                        } else {
+                            history.undo(&edited)
+                        }
+                    });
+                    if let Some(state) = state {
+                        edited = state;
+                    }
+                    continue;
+                }
+                KeyCode::Left if !shift && !edited.selection().is_empty() => {
+                    let start = edited.selection().start;
+                    edited.move_caret(start, false);
+                }
+                KeyCode::Right if !shift && !edited.selection().is_empty() => {
+                    let end = edited.selection().end;
+                    edited.move_caret(end, false);
+                }
+                KeyCode::Left => {
+                    let position = if control {
+                        edited.previous_word(edited.caret)
+                    } else {
+                        edited.caret.saturating_sub(1)
+                    };
+                    edited.move_caret(position, shift);
+                }
+                KeyCode::Right => {
+                    let position = if control {
+                        edited.next_word(edited.caret)
+                    } else {
+                        edited.caret + 1
+                    };
+                    edited.move_caret(position, shift);
+                }
+                KeyCode::Up | KeyCode::Down if settings.multiline => {
+                    let position = edited.vertical(edited.caret, *key == KeyCode::Down, &rows);
+                    edited.move_caret(position, shift);
+                }
+                KeyCode::Home if settings.multiline && !control => {
+                    edited.move_caret(edited.line_start(edited.caret, &rows), shift)
+                }
+                KeyCode::End if settings.multiline && !control => {
+                    edited.move_caret(edited.line_end(edited.caret, &rows), shift)
+                }
+                KeyCode::Home => edited.move_caret(0, shift),
+                KeyCode::End => edited.move_caret(edited.len(), shift),
+                KeyCode::Back | KeyCode::Delete => {
+                    if edited.selection().is_empty() {
+                        let position = match (key, control) {
+                            (KeyCode::Back, true) => edited.previous_word(edited.caret),
+                            (KeyCode::Back, false) => edited.caret.saturating_sub(1),
+                            (_, true) => edited.next_word(edited.caret),
+                            (_, false) => edited.caret + 1,
+                        };
+                        edited.move_caret(position, true);
+                    }
+                    edited.delete_selection();
+                }
+                KeyCode::A if command => edited.select_all(),
+                KeyCode::C | KeyCode::X if command && settings.mask.is_none() => {
+                    if !edited.selection().is_empty() {
+                        clipboard.0 = edited.selected_text().to_string();
+                        if *key == KeyCode::X {
+                            edited.delete_selection();
+                        }
+                    }
+                }
+                KeyCode::V if command => edited.insert(&clipboard.0, settings),
+                KeyCode::Return | KeyCode::NumpadEnter if settings.multiline && !control => {
+                    edited.insert("\n", settings)
+                }
+                KeyCode::Return | KeyCode::NumpadEnter => submitted = true,
+                KeyCode::Escape => {
+                    commands.entity(entity).remove::<Focused>();
+                }
+                _ if !types_char(*key) => {}
+                _ => {
+                    if let Some(c) = typed.next() {
+                        edited.insert(c.encode_utf8(&mut [0; 4]), settings);
+                        typing = true;
+                    }
+                }
+            }
+            if let Some(history) = history.as_mut() {
+                if edited.value != before.value {
+                    history.record(&before, typing);
+                } else if edited != before {
+                    history.interrupt();
+                }
+            }
+        }
+        // Chars typed without a key press, e.g. by an input method.
+        let remaining: String = typed.collect();
+        if !remaining.is_empty() {
+            let before = edited.clone();
+            edited.insert(&remaining, settings);
+            if let Some(history) = history.as_mut().filter(|_| edited.value != before.value) {
+                history.record(&before, true);
+            }
+        }
+
+        if edited != *input {
+            if edited.value != input.value {
+                changed_events.send(TextInputChanged {
+                    entity,
+                    value: edited.value.clone(),
+                });
+            }
+            *input = edited;
+        }
+        if submitted {
+            submitted_events.send(TextInputSubmitted {
+                entity,
+                value: input.value.clone(),
+            });
+        }
+    }
+}
+
+/// Vertical extent of the glyph of each char of the value, found from the sections of the text
+/// displaying it.
+fn glyph_extents(
+    input: &TextInput,
+    text: &Text,
+    layout: &TextLayoutInfo,
+) -> Vec<Option<(f32, f32)>> {
+    let selection = input.selection();
+    // Position in the value of the first char of each section, for sections displaying the value.
+    let starts = [
+        Some(0),
+        None,
+        Some(selection.start),
+        None,
+        Some(selection.end),
+        None,
+    ];
+    let mut extents = vec![None; input.len()];
+    for glyph in &layout.glyphs {
+        let start = match starts.get(glyph.section_index).copied().flatten() {
+            Some(start) => start,
+            None => continue,
+        };
+        let offset = text
+            .sections
+            .get(glyph.section_index)
+            .and_then(|section| section.value.get(..glyph.byte_index))
+            .map_or(0, |value| value.chars().count());
+        if let Some(extent) = extents.get_mut(start + offset) {
+            let half_height = glyph.size.y / 2.0;
+            *extent = Some((
+                glyph.position.y - half_height,
+                glyph.position.y + half_height,
+            ));
+        }
+    }
+    extents
+}
+
+/// Whether a key types a char when it's pressed without command modifiers.
+fn types_char(key: KeyCode) -> bool {
+    !matches!(
+        key,
+        KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LControl
+            | KeyCode::RControl
+            | KeyCode::LAlt
+            | KeyCode::RAlt
+            | KeyCode::LWin
+            | KeyCode::RWin
+            | KeyCode::Capital
+            | KeyCode::Tab
+            | KeyCode::Insert
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+    )
+}
+
+/// Updates the sections of the text node when the input, its settings or its focus change.
+fn text_input_display(
+    input_q: Query<(
+        Entity,
+        &TextInput,
+        &TextInputSettings,
+        &TextInputNodes,
+        Option<&Focused>,
+        ChangeTrackers<TextInput>,
+        ChangeTrackers<TextInputSettings>,
+        Option<ChangeTrackers<Focused>>,
+    )>,
+    removed_focused: RemovedComponents<Focused>,
+    mut text_q: Query<&mut Text, With<TextInputText>>,
+) {
+    let removed: Vec<_> = removed_focused.iter().collect();
+    for (
+        entity,
+        input,
+        settings,
+        nodes,
+        focused,
+        input_tracker,
+        settings_tracker,
+        focused_tracker,
+    ) in input_q.iter()
+    {
+        if !input_tracker.is_changed()
+            && !settings_tracker.is_changed()
+            && !focused_tracker.map_or(false, |tracker| tracker.is_changed())
+            && !removed.contains(&entity)
+        {
+            continue;
+        }
+        let mut text = match text_q.get_mut(nodes.text) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+
+        let selection = input.selection();
+        let display = |range: Range<usize>| input.display(range, settings.mask);
+        let caret = if focused.is_some() { "|" } else { "" };
+        let (caret_before, caret_after) = if input.caret == selection.start {
+            (caret, "")
+        } else {
+            ("", caret)
+        };
+        let placeholder = if input.is_empty() {
+            settings.placeholder.as_str()
+        } else {
+            ""
+        };
+        let values: [String; 6] = [
+            display(0..selection.start),
+            caret_before.to_string(),
+            display(selection.clone()),
+            caret_after.to_string(),
+            display(selection.end..input.len()),
+            placeholder.to_string(),
+        ];
+
+        for (section, value) in text.sections.iter_mut().zip(values) {
+            if section.value != value {
+                section.value = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(value: &str, selection: Range<usize>) -> TextInput {
+        let mut input = TextInput::new(value);
+        input.select(selection);
+        input
+    }
+
+    #[test]
+    fn selection_is_ordered_from_anchor_to_caret() {
+        let input = input("hello world", 8..2);
+        assert_eq!(input.selection(), 2..8);
+        assert_eq!(input.caret(), 2);
+        assert_eq!(input.selected_text(), "llo wo");
+    }
+
+    #[test]
+    fn selection_counts_chars_rather_than_bytes() {
+        let input = input("héllo", 1..3);
+        assert_eq!(input.len(), 5);
+        assert_eq!(input.selected_text(), "él");
+    }
+
+    #[test]
+    fn select_is_clamped_to_the_value() {
+        let mut input = TextInput::new("abc");
+        input.select(1..10);
+        assert_eq!(input.selection(), 1..3);
+        input.select_all();
+        assert_eq!(input.selected_text(), "abc");
+    }
+
+    #[test]
+    fn move_caret_extends_or_collapses_the_selection() {
+        let mut input = input("abcdef", 2..2);
+        input.move_caret(4, true);
+        assert_eq!(input.selection(), 2..4);
+        input.move_caret(10, false);
+        assert_eq!(input.caret(), 6);
+        assert!(input.selection().is_empty());
+    }
+
+    #[test]
+    fn word_moves_skip_whitespace() {
+        let input = TextInput::new("one  two three");
+        assert_eq!(input.previous_word(14), 9);
+        assert_eq!(input.previous_word(9), 5);
+        assert_eq!(input.previous_word(5), 0);
+        assert_eq!(input.previous_word(0), 0);
+        assert_eq!(input.next_word(0), 3);
+        assert_eq!(input.next_word(3), 8);
+        assert_eq!(input.next_word(9), 14);
+        assert_eq!(input.next_word(14), 14);
+    }
+
+    #[test]
+    fn insert_replaces_the_selection() {
+        let mut input = input("hello world", 6..11);
+        input.insert("there", &TextInputSettings::default());
+        assert_eq!(input.value(), "hello there");
+        assert_eq!(input.caret(), 11);
+        assert!(input.selection().is_empty());
+    }
+
+    #[test]
+    fn insert_respects_max_length() {
+        let settings = TextInputSettings {
+            max_length: Some(5),
+            ..Default::default()
+        };
+        let mut input = TextInput::new("abc");
+        input.insert("defgh", &settings);
+        assert_eq!(input.value(), "abcde");
+
+        // The selection is replaced, so its chars are available.
+        let mut input = self::input("abcde", 1..4);
+        input.insert("xyz", &settings);
+        assert_eq!(input.value(), "axyze");
+    }
+
+    #[test]
+    fn insert_keeps_only_accepted_chars() {
+        let settings = TextInputSettings {
+            filter: CharacterFilter::Digits,
+            ..Default::default()
+        };
+        let mut input = TextInput::new("1");
+        input.insert("2a3", &settings);
+        assert_eq!(input.value(), "123");
+
+        let custom = TextInputSettings {
+            filter: CharacterFilter::Custom(|c| c == 'x'),
+            ..Default::default()
+        };
+        let mut input = TextInput::new("");
+        input.insert("axbx", &custom);
+        assert_eq!(input.value(), "xx");
+    }
+
+    #[test]
+    fn rejected_chars_keep_the_selection() {
+        let settings = TextInputSettings {
+            filter: CharacterFilter::Digits,
+            ..Default::default()
+        };
+        let mut input = input("1234", 1..3);
+        input.insert("a", &settings);
+        assert_eq!(input.value(), "1234");
+        assert_eq!(input.selection(), 1..3);
+    }
+
+    #[test]
+    fn inserting_nothing_deletes_the_selection() {
+        let mut input = input("1234", 1..3);
+        input.insert("", &TextInputSettings::default());
+        assert_eq!(input.value(), "14");
+        assert_eq!(input.caret(), 1);
+    }
+
+    #[test]
+    fn line_breaks_are_only_inserted_in_multiline_inputs() {
+        let mut input = TextInput::new("a");
+        input.insert("\nb", &TextInputSettings::default());
+        assert_eq!(input.value(), "ab");
+
+        let multiline = TextInputSettings {
+            multiline: true,
+            ..Default::default()
+        };
+        input.insert("\nc", &multiline);
+        assert_eq!(input.value(), "ab\nc");
+    }
+
+    #[test]
+    fn line_breaks_are_not_filtered() {
+        let settings = TextInputSettings {
+            multiline: true,
+            filter: CharacterFilter::Digits,
+            ..Default::default()
+        };
+        let mut input = TextInput::new("1");
+        input.insert("\n2a\n", &settings);
+        assert_eq!(input.value(), "1\n2\n");
+    }
+
+    #[test]
+    fn delete_selection_moves_the_caret_to_its_start() {
+        let mut input = input("abcdef", 4..1);
+        input.delete_selection();
+        assert_eq!(input.value(), "aef");
+        assert_eq!(input.caret(), 1);
+    }
+
+    #[test]
+    fn rows_are_split_at_line_breaks() {
+        let input = TextInput::new("ab\n\ncd");
+        assert_eq!(input.rows(&[]), vec![0..2, 3..3, 4..6]);
+    }
+
+    #[test]
+    fn rows_are_split_where_glyphs_wrap() {
+        // "ab cd\nef" with "cd" wrapped on a second row, and no glyph for spaces and line breaks.
+        let input = TextInput::new("ab cd\nef");
+        let first = Some((40.0, 50.0));
+        let second = Some((20.0, 30.0));
+        let third = Some((0.0, 10.0));
+        let extents = [first, first, None, second, second, None, third, third];
+        assert_eq!(input.rows(&extents), vec![0..3, 3..5, 6..8]);
+    }
+
+    #[test]
+    fn vertical_moves_keep_the_column() {
+        let input = TextInput::new("abcd\nab\nabcdef");
+        let rows = input.rows(&[]);
+        assert_eq!(input.vertical(3, true, &rows), 7);
+        assert_eq!(input.vertical(7, true, &rows), 10);
+        assert_eq!(input.vertical(10, false, &rows), 7);
+        assert_eq!(input.vertical(14, false, &rows), 7);
+        assert_eq!(input.vertical(2, false, &rows), 0);
+        assert_eq!(input.vertical(10, true, &rows), input.len());
+    }
+
+    #[test]
+    fn wrapped_rows_end_before_the_next_row() {
+        let input = TextInput::new("ab cd");
+        let first = Some((20.0, 30.0));
+        let second = Some((0.0, 10.0));
+        let rows = input.rows(&[first, first, None, second, second]);
+        assert_eq!(input.line_end(1, &rows), 2);
+        assert_eq!(input.line_start(4, &rows), 3);
+        assert_eq!(input.line_end(4, &rows), 5);
+        assert_eq!(input.vertical(4, false, &rows), 1);
+        assert_eq!(input.vertical(1, true, &rows), 4);
+    }
+
+    #[test]
+    fn mask_replaces_every_displayed_char() {
+        let input = TextInput::new("sécret");
+        assert_eq!(input.display(0..6, Some('*')), "******");
+        assert_eq!(input.display(1..3, Some('*')), "**");
+        assert_eq!(input.display(1..3, None), "éc");
+    }
+}
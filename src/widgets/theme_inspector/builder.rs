@@ -0,0 +1,260 @@
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_render::prelude::*;
+use bevy_text::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+use crate::widgets::{
+    frame::{FrameWidgetBuilder, FrameWidgetEntities},
+    slider::{SliderBundle, SliderWidgetBuilder},
+};
+
+use super::*;
+
+/// Builds a theme inspector inside a frame.
+pub struct ThemeInspectorWidgetBuilder<'a, 'w, 's> {
+    frame: FrameWidgetBuilder<'a, 'w, 's>,
+    text_style: TextStyle,
+    fields: Vec<InspectorField>,
+}
+
+pub struct ThemeInspectorWidgetEntities {
+    pub frame: FrameWidgetEntities,
+    /// The frame's content, with the [`ThemeInspector`] component.
+    pub inspector: Entity,
+    pub keys_text: Entity,
+    pub properties_text: Entity,
+    /// The slider or stepper of each field, with the [`ThemeInspectorField`] component.
+    pub fields: Vec<Entity>,
+}
+
+impl Default for ThemeInspectorWidgetBuilder<'_, '_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'w, 's> ThemeInspectorWidgetBuilder<'a, 'w, 's> {
+    /// Creates a new theme inspector builder
+    pub fn new() -> Self {
+        let mut frame = FrameWidgetBuilder::new();
+        frame
+            .root_bundle(|bundle| NodeBundle {
+                style: Style {
+                    position: UiRect {
+                        left: Val::Px(20.0),
+                        top: Val::Px(20.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(320.0), Val::Undefined),
+                    ..bundle.style
+                },
+                color: Color::rgb(0.05, 0.05, 0.05).into(),
+                ..bundle
+            })
+            .title_bar_bundle(|bundle| ButtonBundle {
+                color: Color::rgb(0.15, 0.15, 0.15).into(),
+                ..bundle
+            });
+        Self {
+            frame,
+            text_style: TextStyle {
+                font_size: 14.0,
+                color: Color::rgb(0.9, 0.9, 0.9),
+                ..default()
+            },
+            fields: InspectorField::ALL.to_vec(),
+        }
+    }
+
+    /// Allows to customize the frame containing the inspector.
+    pub fn frame(&mut self, edit: impl FnOnce(&mut FrameWidgetBuilder<'a, 'w, 's>)) -> &mut Self {
+        edit(&mut self.frame);
+        self
+    }
+
+    /// Sets the style of every text of the inspector, including the frame's title.
+    /// The default style has no font, so this should be set for texts to be displayed.
+    /// When the built-in theme is active, the colour is overridden by the `ThemeInspectorText`
+    /// selector.
+    pub fn text_style(&mut self, text_style: TextStyle) -> &mut Self {
+        self.text_style = text_style;
+        self
+    }
+
+    /// Sets the fields used to edit the selected entity, which are all displayed by default.
+    pub fn fields(&mut self, fields: impl IntoIterator<Item = InspectorField>) -> &mut Self {
+        self.fields = fields.into_iter().collect();
+        self
+    }
+
+    /// Spawns the entity and returns the entities of the inspector.
+    /// Using the builder again after calling this will panic.
+    pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> ThemeInspectorWidgetEntities {
+        let inspector = commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::ColumnReverse,
+                    align_items: AlignItems::Stretch,
+                    margin: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })
+            .insert(ThemeInspector::default())
+            .id();
+
+        let keys_title = self.spawn_text(commands, "Theme keys");
+        let keys_text = self.spawn_text(commands, "");
+        commands.entity(keys_text).insert(ThemeInspectorKeysText);
+        let properties_title = self.spawn_text(commands, "Properties");
+        let properties_text = self.spawn_text(commands, "");
+        commands
+            .entity(properties_text)
+            .insert(ThemeInspectorPropertiesText(inspector));
+        let fields_title = self.spawn_text(commands, "Click an entity to edit it");
+        commands.entity(inspector).push_children(&[
+            keys_title,
+            keys_text,
+            properties_title,
+            properties_text,
+            fields_title,
+        ]);
+
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for field in self.fields.clone() {
+            let component = ThemeInspectorField { inspector, field };
+            let label = self.spawn_text(commands, &field.label());
+            let input = if field.is_color() {
+                self.spawn_slider(commands, component)
+            } else {
+                self.spawn_stepper(commands, component)
+            };
+            let row = commands
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        margin: UiRect {
+                            top: Val::Px(2.0),
+                            bottom: Val::Px(2.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    color: Color::NONE.into(),
+                    ..default()
+                })
+                .push_children(&[label, input])
+                .id();
+            commands.entity(inspector).add_child(row);
+            fields.push(input);
+        }
+
+        let title_style = self.text_style.clone();
+        let frame = self
+            .frame
+            .title_text_bundle(|bundle| TextBundle {
+                text: Text::from_section("Theme inspector", title_style),
+                ..bundle
+            })
+            .with_content(inspector)
+            .spawn(commands);
+
+        ThemeInspectorWidgetEntities {
+            frame,
+            inspector,
+            keys_text,
+            properties_text,
+            fields,
+        }
+    }
+
+    fn spawn_text(&self, commands: &mut Commands, value: &str) -> Entity {
+        commands
+            .spawn_bundle(TextBundle::from_section(value, self.text_style.clone()))
+            .insert(ThemeInspectorText)
+            .id()
+    }
+
+    /// Spawns a slider from 0 to 255, used for colour channels.
+    fn spawn_slider(&self, commands: &mut Commands, field: ThemeInspectorField) -> Entity {
+        SliderWidgetBuilder::new()
+            .root_bundle(|bundle| SliderBundle {
+                slider: Slider {
+                    value: 0,
+                    min: 0,
+                    max: 255,
+                    step: 1,
+                },
+                // The value label is below the track.
+                style: Style {
+                    size: Size::new(Val::Px(150.0), Val::Px(34.0)),
+                    justify_content: JustifyContent::FlexStart,
+                    ..bundle.style
+                },
+                ..bundle
+            })
+            .root_commands(move |commands| {
+                commands.insert(field);
+            })
+            .with_value_label(self.text_style.clone())
+            .spawn(commands)
+            .root
+    }
+
+    /// Spawns a value between a decrease and an increase button, used for sizes.
+    fn spawn_stepper(&self, commands: &mut Commands, field: ThemeInspectorField) -> Entity {
+        let stepper = commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })
+            .insert(field)
+            .insert(ThemeInspectorStepper::default())
+            .id();
+        let step = field.field.step();
+        let decrease = self.spawn_stepper_button(commands, stepper, "-", -step);
+        let value = self.spawn_text(commands, "");
+        commands
+            .entity(value)
+            .insert(ThemeInspectorStepperText(stepper));
+        let increase = self.spawn_stepper_button(commands, stepper, "+", step);
+        commands
+            .entity(stepper)
+            .push_children(&[decrease, value, increase]);
+        stepper
+    }
+
+    fn spawn_stepper_button(
+        &self,
+        commands: &mut Commands,
+        stepper: Entity,
+        label: &str,
+        delta: i32,
+    ) -> Entity {
+        let text = self.spawn_text(commands, label);
+        commands
+            .spawn_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(20.0), Val::Px(20.0)),
+                    margin: UiRect::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: Color::rgb(0.15, 0.15, 0.15).into(),
+                ..default()
+            })
+            .insert(ThemeInspectorStepperButton { stepper, delta })
+            .add_child(text)
+            .id()
+    }
+}
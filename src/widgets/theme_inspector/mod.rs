@@ -0,0 +1,669 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use bevy_app::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_hierarchy::prelude::*;
+use bevy_input::prelude::*;
+use bevy_render::prelude::*;
+use bevy_text::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_ui::{prelude::*, CalculatedClip, UiSystem};
+use bevy_window::prelude::*;
+
+mod builder;
+pub use builder::*;
+
+use crate::{
+    theming::{
+        ColorProperty, ResolvedProperty, SizeProperty, TextColorProperty, TextFontSizeProperty,
+        ThemeKey, ThemeManager, ThemeMarkerAppExt, ThemeMarkers, ThemeNodeQuery,
+    },
+    utils::*,
+    widgets::slider::Slider,
+};
+
+pub struct ThemeInspectorPlugin;
+
+impl Plugin for ThemeInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_theme_marker::<ThemeInspector>("ThemeInspector")
+            .register_theme_marker::<ThemeInspectorText>("ThemeInspectorText")
+            .register_theme_marker::<ThemeInspectorStepperButton>("ThemeInspectorStepperButton")
+            .add_system(update_inspected_entity.label(ThemeInspectorSystem::Pick))
+            .add_system(update_keys_text)
+            .add_system(update_properties_text.after(ThemeInspectorSystem::Pick))
+            .add_system(update_steppers)
+            // Fields are synced after sliders were moved during the update, and before the layout
+            // so that stepper values are displayed immediately.
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                write_fields
+                    .label(ThemeInspectorSystem::WriteFields)
+                    .before(UiSystem::Flex),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                read_fields
+                    .label(ThemeInspectorSystem::ReadFields)
+                    .after(ThemeInspectorSystem::WriteFields)
+                    .before(UiSystem::Flex),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_stepper_text
+                    .after(ThemeInspectorSystem::ReadFields)
+                    .before(UiSystem::Flex),
+            );
+    }
+}
+
+/// Labels for the theme inspector systems.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum ThemeInspectorSystem {
+    /// Finds the entity under the cursor and selects it when clicked.
+    Pick,
+    /// Writes the values of fields changed by the user to the [`ThemeManager`].
+    WriteFields,
+    /// Reads the values of fields from the [`ThemeManager`] when the selected entity changes.
+    ReadFields,
+}
+
+/// A debugging panel showing the theme properties of the entity under the cursor.
+///
+/// Clicking a themed entity selects it, so that its properties can be edited with the inspector's
+/// fields. Edits are written to the selector that set the property, or to the most specific
+/// selector matching the entity, and are applied live to every entity it matches.
+#[derive(Component, Default, Debug)]
+pub struct ThemeInspector {
+    /// The themed entity under the cursor, outside of the inspector.
+    pub hovered: Option<Entity>,
+    /// The entity edited by the inspector's fields.
+    pub selected: Option<Entity>,
+}
+
+impl ThemeInspector {
+    /// Returns the entity whose properties are displayed: the selected entity, or the hovered one.
+    pub fn target(&self) -> Option<Entity> {
+        self.selected.or(self.hovered)
+    }
+}
+
+/// Marker component for every text of the inspector's content.
+#[derive(Component)]
+pub struct ThemeInspectorText;
+
+/// Marker component for the text listing the [`ThemeKey`]s in use.
+#[derive(Component)]
+pub struct ThemeInspectorKeysText;
+
+/// Added to the text listing the properties of an inspector's target.
+#[derive(Component)]
+pub struct ThemeInspectorPropertiesText(pub Entity);
+
+/// Added to a slider or a [`ThemeInspectorStepper`] editing a property of the selected entity.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ThemeInspectorField {
+    /// The entity with the [`ThemeInspector`].
+    pub inspector: Entity,
+    pub field: InspectorField,
+}
+
+/// A numeric field edited with buttons, used for sizes.
+#[derive(Component, Default, Debug)]
+pub struct ThemeInspectorStepper {
+    pub value: i32,
+}
+
+/// Added to a button changing the value of a [`ThemeInspectorStepper`] when clicked.
+#[derive(Component)]
+pub struct ThemeInspectorStepperButton {
+    pub stepper: Entity,
+    pub delta: i32,
+}
+
+/// Added to the text displaying the value of a [`ThemeInspectorStepper`].
+#[derive(Component)]
+pub struct ThemeInspectorStepperText(pub Entity);
+
+/// A value that can be edited by the inspector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InspectorField {
+    /// A channel of the `background-color` property, from 0 to 255.
+    BackgroundColor(ColorChannel),
+    /// A channel of the text `color` property, from 0 to 255.
+    TextColor(ColorChannel),
+    /// The width of the `size` property, in pixels.
+    Width,
+    /// The height of the `size` property, in pixels.
+    Height,
+    /// The `font-size` property.
+    FontSize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChannel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl ColorChannel {
+    fn index(self) -> usize {
+        match self {
+            ColorChannel::Red => 0,
+            ColorChannel::Green => 1,
+            ColorChannel::Blue => 2,
+            ColorChannel::Alpha => 3,
+        }
+    }
+
+    fn get(self, color: Color) -> i32 {
+        (color.as_rgba_f32()[self.index()] * 255.0).round() as i32
+    }
+
+    fn set(self, color: Color, value: i32) -> Color {
+        let mut rgba = color.as_rgba_f32();
+        rgba[self.index()] = value as f32 / 255.0;
+        Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3])
+    }
+}
+
+impl InspectorField {
+    /// Every field, in the order they are displayed by default.
+    pub const ALL: [InspectorField; 11] = [
+        InspectorField::BackgroundColor(ColorChannel::Red),
+        InspectorField::BackgroundColor(ColorChannel::Green),
+        InspectorField::BackgroundColor(ColorChannel::Blue),
+        InspectorField::BackgroundColor(ColorChannel::Alpha),
+        InspectorField::TextColor(ColorChannel::Red),
+        InspectorField::TextColor(ColorChannel::Green),
+        InspectorField::TextColor(ColorChannel::Blue),
+        InspectorField::TextColor(ColorChannel::Alpha),
+        InspectorField::Width,
+        InspectorField::Height,
+        InspectorField::FontSize,
+    ];
+
+    pub fn label(&self) -> String {
+        let channel = |channel: &ColorChannel| format!("{:?}", channel);
+        match self {
+            InspectorField::BackgroundColor(c) => format!("Background {}", channel(c)),
+            InspectorField::TextColor(c) => format!("Text {}", channel(c)),
+            InspectorField::Width => "Width".to_string(),
+            InspectorField::Height => "Height".to_string(),
+            InspectorField::FontSize => "Font size".to_string(),
+        }
+    }
+
+    /// Returns `true` if the field is edited with a slider, or `false` for a stepper.
+    pub fn is_color(&self) -> bool {
+        matches!(
+            self,
+            InspectorField::BackgroundColor(_) | InspectorField::TextColor(_)
+        )
+    }
+
+    /// Value added or removed by the buttons of a stepper.
+    pub fn step(&self) -> i32 {
+        match self {
+            InspectorField::FontSize => 1,
+            _ => 5,
+        }
+    }
+
+    fn property_name(&self) -> &'static str {
+        match self {
+            InspectorField::BackgroundColor(_) => "background-color",
+            InspectorField::TextColor(_) => "color",
+            InspectorField::Width | InspectorField::Height => "size",
+            InspectorField::FontSize => "font-size",
+        }
+    }
+
+    fn read(&self, node: &InspectedNode) -> i32 {
+        let px = |val: Val, computed: f32| match val {
+            Val::Px(px) => px,
+            _ => computed,
+        };
+        match self {
+            InspectorField::BackgroundColor(channel) => channel.get(node.background_color()),
+            InspectorField::TextColor(channel) => channel.get(node.text_color()),
+            InspectorField::Width => {
+                px(node.size().width, node.node.map_or(0.0, |node| node.size.x)).round() as i32
+            }
+            InspectorField::Height => px(
+                node.size().height,
+                node.node.map_or(0.0, |node| node.size.y),
+            )
+            .round() as i32,
+            InspectorField::FontSize => node.font_size().round() as i32,
+        }
+    }
+
+    /// Returns the property value with the field set to `value`, keeping the other parts of the
+    /// property, e.g. the other colour channels.
+    fn edit(&self, node: &InspectedNode, value: i32) -> FieldValue {
+        match self {
+            InspectorField::BackgroundColor(channel) => {
+                FieldValue::BackgroundColor(channel.set(node.background_color(), value))
+            }
+            InspectorField::TextColor(channel) => {
+                FieldValue::TextColor(channel.set(node.text_color(), value))
+            }
+            InspectorField::Width => FieldValue::Size(Size {
+                width: Val::Px(value as f32),
+                ..node.size()
+            }),
+            InspectorField::Height => FieldValue::Size(Size {
+                height: Val::Px(value as f32),
+                ..node.size()
+            }),
+            InspectorField::FontSize => FieldValue::FontSize(value as f32),
+        }
+    }
+}
+
+/// A property edited by a field, written to the [`ThemeManager`] once the inspected node is
+/// no longer borrowed.
+enum FieldValue {
+    BackgroundColor(Color),
+    TextColor(Color),
+    Size(Size<Val>),
+    FontSize(f32),
+}
+
+impl FieldValue {
+    fn apply(self, theme: &mut ThemeManager, selector: &str) {
+        match self {
+            FieldValue::BackgroundColor(color) => {
+                theme.set_property(selector, ColorProperty(color))
+            }
+            FieldValue::TextColor(color) => theme.set_property(selector, TextColorProperty(color)),
+            FieldValue::Size(size) => theme.set_property(selector, SizeProperty(size)),
+            FieldValue::FontSize(size) => theme.set_property(selector, TextFontSizeProperty(size)),
+        };
+    }
+}
+
+/// Entities that can be inspected, along with the components used when their properties are unset.
+#[derive(SystemParam)]
+struct InspectedNodes<'w, 's> {
+    query: ThemeNodeQuery<'w, 's>,
+    components: Query<
+        'w,
+        's,
+        (
+            Option<&'static Node>,
+            Option<&'static Style>,
+            Option<&'static Text>,
+        ),
+    >,
+}
+
+impl<'w, 's> InspectedNodes<'w, 's> {
+    fn get<'t>(&self, entity: Entity, theme: &'t ThemeManager) -> Option<InspectedNode<'t, '_>> {
+        let (node, style, text) = self.components.get(entity).ok()?;
+        let selector = theme
+            .matching_rules(entity, &self.query)
+            .first()
+            .map(|rule| theme.active_theme().rule_key(*rule).to_string())
+            .or_else(|| {
+                let node = self.query.get(entity).ok()?;
+                match (node.key, node.markers) {
+                    (Some(key), _) if key.id.is_some() || !key.classes.is_empty() => {
+                        Some(key_selector(key))
+                    }
+                    (_, Some(markers)) => markers.iter().next().map(String::from),
+                    _ => None,
+                }
+            });
+        Some(InspectedNode {
+            properties: theme.resolved_properties(entity, &self.query),
+            selector,
+            node,
+            style,
+            text,
+        })
+    }
+}
+
+/// The properties and components of an inspected entity.
+struct InspectedNode<'t, 'q> {
+    properties: Vec<ResolvedProperty<'t>>,
+    /// The most specific selector matching the entity, used to set properties it doesn't have yet.
+    selector: Option<String>,
+    node: Option<&'q Node>,
+    style: Option<&'q Style>,
+    text: Option<&'q Text>,
+}
+
+impl<'t, 'q> InspectedNode<'t, 'q> {
+    /// Returns the selector to edit for a field: the one that set its property, if any.
+    fn field_selector(&self, field: InspectorField) -> Option<String> {
+        self.properties
+            .iter()
+            .find(|resolved| resolved.name == field.property_name())
+            .map(|resolved| resolved.selector.to_string())
+            .or_else(|| self.selector.clone())
+    }
+
+    fn background_color(&self) -> Color {
+        ResolvedProperty::find::<ColorProperty>(&self.properties)
+            .map_or(Color::WHITE, |(_, property)| property.0)
+    }
+
+    fn text_color(&self) -> Color {
+        ResolvedProperty::find::<TextColorProperty>(&self.properties)
+            .map(|(_, property)| property.0)
+            .or_else(|| self.first_section().map(|section| section.style.color))
+            .unwrap_or(Color::WHITE)
+    }
+
+    fn size(&self) -> Size<Val> {
+        ResolvedProperty::find::<SizeProperty>(&self.properties)
+            .map(|(_, property)| property.0)
+            .or_else(|| self.style.map(|style| style.size))
+            .unwrap_or(Size::new(Val::Auto, Val::Auto))
+    }
+
+    fn font_size(&self) -> f32 {
+        ResolvedProperty::find::<TextFontSizeProperty>(&self.properties)
+            .map(|(_, property)| property.0)
+            .or_else(|| self.first_section().map(|section| section.style.font_size))
+            .unwrap_or(0.0)
+    }
+
+    fn first_section(&self) -> Option<&TextSection> {
+        self.text.and_then(|text| text.sections.first())
+    }
+}
+
+/// Formats a [`ThemeKey`] as the compound selector matching it, e.g. `#volume.slider.large`.
+fn key_selector(key: &ThemeKey) -> String {
+    let id = key.id.iter().map(|id| format!("#{}", id));
+    let classes = key.classes.iter().map(|class| format!(".{}", class));
+    id.chain(classes).collect()
+}
+
+/// Returns `true` if `entity` is `ancestor` or one of its descendants.
+fn is_descendant_of(entity: Entity, ancestor: Entity, parents: &Query<&Parent>) -> bool {
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        if entity == ancestor {
+            return true;
+        }
+        current = parents.get(entity).ok().map(|parent| parent.get());
+    }
+    false
+}
+
+/// Finds the topmost themed entity under the cursor, and selects it when the left button is clicked.
+fn update_inspected_entity(
+    mut inspector_q: Query<(Entity, &mut ThemeInspector, Option<&Parent>)>,
+    node_q: Query<
+        (
+            Entity,
+            &Node,
+            &GlobalTransform,
+            Option<&CalculatedClip>,
+            &ComputedVisibility,
+        ),
+        Or<(With<ThemeKey>, With<ThemeMarkers>)>,
+    >,
+    parent_q: Query<&Parent>,
+    windows: Res<Windows>,
+    mouse: Res<Input<MouseButton>>,
+) {
+    if inspector_q.is_empty() {
+        return;
+    }
+    let hovered = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+        .and_then(|cursor| {
+            node_q
+                .iter()
+                .filter(|(_, node, global_transform, clip, visibility)| {
+                    let (min, max) = get_uinode_clipped_rect(global_transform, node, *clip);
+                    visibility.is_visible() && cursor.cmpge(min).all() && cursor.cmple(max).all()
+                })
+                .max_by(|(_, _, a, ..), (_, _, b, ..)| {
+                    let (a, b) = (a.translation().z, b.translation().z);
+                    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                })
+                .map(|(entity, ..)| entity)
+        });
+
+    for (entity, mut inspector, parent) in inspector_q.iter_mut() {
+        // The inspector is the content of a frame, whose nodes can't be inspected.
+        let frame = parent.map_or(entity, |parent| parent.get());
+        if hovered.map_or(false, |hovered| is_descendant_of(hovered, frame, &parent_q)) {
+            continue;
+        }
+        if inspector.hovered != hovered {
+            inspector.hovered = hovered;
+        }
+        if mouse.just_pressed(MouseButton::Left) && inspector.selected != hovered {
+            inspector.selected = hovered;
+        }
+    }
+}
+
+/// Lists the [`ThemeKey`]s in use along with the number of entities using them.
+fn update_keys_text(
+    mut text_q: Query<
+        (&mut Text, ChangeTrackers<ThemeInspectorKeysText>),
+        With<ThemeInspectorKeysText>,
+    >,
+    key_q: Query<&ThemeKey>,
+    changed_q: Query<(), Changed<ThemeKey>>,
+    mut key_count: Local<usize>,
+) {
+    // Removed keys are detected from the number of keyed entities rather than with
+    // `RemovedComponents`, which misses keys removed after this system ran in the previous frame.
+    let count = key_q.iter().count();
+    let keys_changed = !changed_q.is_empty() || count != *key_count;
+    *key_count = count;
+    let mut list = None;
+    for (mut text, tracker) in text_q.iter_mut() {
+        if !keys_changed && !tracker.is_added() {
+            continue;
+        }
+        let list = list.get_or_insert_with(|| {
+            let mut counts = BTreeMap::<String, usize>::new();
+            for key in key_q.iter() {
+                *counts.entry(key_selector(key)).or_default() += 1;
+            }
+            counts
+                .iter()
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, count)| format!("{} ({})", key, count))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        if let Some(section) = text.sections.first_mut() {
+            section.value.clone_from(list);
+        }
+    }
+}
+
+/// Lists the properties of the inspector's target, along with the selector that set them.
+fn update_properties_text(
+    mut text_q: Query<(&ThemeInspectorPropertiesText, &mut Text)>,
+    inspector_q: Query<&ThemeInspector>,
+    theme: Option<Res<ThemeManager>>,
+    query: ThemeNodeQuery,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    for (properties_text, mut text) in text_q.iter_mut() {
+        let inspector = match inspector_q.get(properties_text.0) {
+            Ok(inspector) => inspector,
+            Err(_) => continue,
+        };
+        let target = inspector
+            .target()
+            .and_then(|target| query.get(target).ok().map(|node| (target, node)));
+        let value = match target {
+            Some((target, node)) => {
+                let label = if inspector.selected.is_some() {
+                    "Selected"
+                } else {
+                    "Hovered"
+                };
+                let mut lines = vec![format!(
+                    "{} {:?} {}{}",
+                    label,
+                    target,
+                    node.key.map(key_selector).unwrap_or_default(),
+                    node.markers
+                        .map(|markers| format!(
+                            " [{}]",
+                            markers.iter().collect::<Vec<_>>().join(", ")
+                        ))
+                        .unwrap_or_default(),
+                )];
+                for resolved in theme.resolved_properties(target, &query) {
+                    let value = ron::to_string(resolved.property.as_serialize())
+                        .unwrap_or_else(|error| error.to_string());
+                    lines.push(format!(
+                        "{}: {} ({})",
+                        resolved.name, value, resolved.selector
+                    ));
+                }
+                lines.join("\n")
+            }
+            None => "Hover a themed entity to inspect it, click to select it.".to_string(),
+        };
+        // Only set the text when it changed, to avoid computing its layout every frame.
+        if let Some(section) = text
+            .sections
+            .first_mut()
+            .filter(|section| section.value != value)
+        {
+            section.value = value;
+        }
+    }
+}
+
+/// Changes the value of steppers when their buttons are clicked.
+fn update_steppers(
+    button_q: Query<(&ThemeInspectorStepperButton, &Interaction), Changed<Interaction>>,
+    mut stepper_q: Query<&mut ThemeInspectorStepper>,
+) {
+    for (button, interaction) in button_q.iter() {
+        if *interaction == Interaction::Clicked {
+            if let Ok(mut stepper) = stepper_q.get_mut(button.stepper) {
+                stepper.value = (stepper.value + button.delta).max(0);
+            }
+        }
+    }
+}
+
+fn update_stepper_text(
+    mut text_q: Query<(&ThemeInspectorStepperText, &mut Text)>,
+    stepper_q: Query<&ThemeInspectorStepper, Changed<ThemeInspectorStepper>>,
+) {
+    for (stepper_text, mut text) in text_q.iter_mut() {
+        if let Ok(stepper) = stepper_q.get(stepper_text.0) {
+            if let Some(section) = text.sections.first_mut() {
+                section.value = stepper.value.to_string();
+            }
+        }
+    }
+}
+
+/// Writes the fields changed by the user to the properties of the selected entity.
+fn write_fields(
+    theme: Option<ResMut<ThemeManager>>,
+    inspector_q: Query<&ThemeInspector>,
+    slider_q: Query<(&ThemeInspectorField, &Slider, ChangeTrackers<Slider>)>,
+    stepper_q: Query<(
+        &ThemeInspectorField,
+        &ThemeInspectorStepper,
+        ChangeTrackers<ThemeInspectorStepper>,
+    )>,
+    nodes: InspectedNodes,
+) {
+    let mut theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    // Values set when fields are spawned or read from the theme aren't edits.
+    let sliders = slider_q
+        .iter()
+        .filter(|(.., tracker)| tracker.is_changed() && !tracker.is_added())
+        .map(|(field, slider, _)| (field, slider.value));
+    let steppers = stepper_q
+        .iter()
+        .filter(|(.., tracker)| tracker.is_changed() && !tracker.is_added())
+        .map(|(field, stepper, _)| (field, stepper.value));
+    for (field, value) in sliders.chain(steppers) {
+        let selected = match inspector_q
+            .get(field.inspector)
+            .ok()
+            .and_then(|inspector| inspector.selected)
+        {
+            Some(selected) => selected,
+            None => continue,
+        };
+        let edit = nodes.get(selected, &theme).and_then(|node| {
+            if field.field.read(&node) == value {
+                return None;
+            }
+            let selector = node.field_selector(field.field)?;
+            Some((selector, field.field.edit(&node, value)))
+        });
+        if let Some((selector, value)) = edit {
+            value.apply(&mut theme, &selector);
+        }
+    }
+}
+
+/// Reads the values of fields from the properties of the selected entity.
+fn read_fields(
+    theme: Option<Res<ThemeManager>>,
+    inspector_q: Query<(&ThemeInspector, ChangeTrackers<ThemeInspector>)>,
+    mut slider_q: Query<(
+        &ThemeInspectorField,
+        &mut Slider,
+        ChangeTrackers<ThemeInspectorField>,
+    )>,
+    mut stepper_q: Query<(
+        &ThemeInspectorField,
+        &mut ThemeInspectorStepper,
+        ChangeTrackers<ThemeInspectorField>,
+    )>,
+    nodes: InspectedNodes,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => return,
+    };
+    let read = |field: &ThemeInspectorField, added: bool| {
+        let (inspector, tracker) = inspector_q.get(field.inspector).ok()?;
+        if !added && !tracker.is_changed() && !theme.is_changed() {
+            return None;
+        }
+        let node = nodes.get(inspector.selected?, &theme)?;
+        Some(field.field.read(&node))
+    };
+    for (field, mut slider, tracker) in slider_q.iter_mut() {
+        if let Some(value) = read(field, tracker.is_added()) {
+            let value = value.clamp(slider.min, slider.max);
+            if slider.value != value {
+                slider.value = value;
+            }
+        }
+    }
+    for (field, mut stepper, tracker) in stepper_q.iter_mut() {
+        if let Some(value) = read(field, tracker.is_added()) {
+            if stepper.value != value {
+                stepper.value = value;
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use bevy_ecs::{prelude::*, system::EntityCommands};
 use bevy_hierarchy::prelude::*;
+use bevy_text::prelude::*;
 use bevy_ui::prelude::*;
 use bevy_utils::prelude::*;
 
@@ -9,11 +10,15 @@ use super::*;
 
 pub struct TooltipWidgetBuilder<'a, 'w, 's> {
     root: WidgetBuilderEntity<'a, 'w, 's, Option<TooltipBundle>>,
+    arrow: Option<WidgetBuilderEntity<'a, 'w, 's, Option<NodeBundle>>>,
+    arrow_color_from_root: bool,
     content_entity: Option<Entity>,
+    text: Option<Text>,
 }
 
 pub struct TooltipWidgetEntities {
     pub root: Entity,
+    pub arrow: Option<Entity>,
     pub content: Option<Entity>,
 }
 
@@ -36,7 +41,10 @@ impl<'a, 'w, 's> TooltipWidgetBuilder<'a, 'w, 's> {
                 },
                 ..default()
             })),
+            arrow: None,
+            arrow_color_from_root: false,
             content_entity: None,
+            text: None,
         }
     }
 
@@ -59,28 +67,122 @@ impl<'a, 'w, 's> TooltipWidgetBuilder<'a, 'w, 's> {
         self
     }
 
+    /// Adds an arrow pointing from the tooltip to its anchor.
+    /// The arrow is a small rotated node that is oriented according to the [`TooltipResolvedAlign`].
+    /// Unless its bundle is edited, the arrow has the same color as the root when spawned.
+    /// Its size and color can be changed like any other node, for example with a theme key.
+    pub fn with_arrow(&mut self) -> &mut Self {
+        if self.arrow.is_none() {
+            self.arrow = Some(WidgetBuilderEntity::new(Some(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Px(8.0), Val::Px(8.0)),
+                    ..default()
+                },
+                ..default()
+            })));
+            self.arrow_color_from_root = true;
+        }
+        self
+    }
+
+    /// Allows to run commands on the arrow entity after it's spawned.
+    /// This enables the arrow if it wasn't already.
+    pub fn arrow_commands(
+        &mut self,
+        run_commands: impl for<'b> Fn(&mut EntityCommands<'w, 's, 'b>) + 'a,
+    ) -> &mut Self {
+        self.with_arrow();
+        if let Some(arrow) = self.arrow.as_mut() {
+            arrow.commands_runners.push(Box::new(run_commands));
+        }
+        self
+    }
+
+    /// Allows to edit the arrow bundle before it is spawned.
+    /// This enables the arrow if it wasn't already.
+    /// It is recommended to keep unmodified original values by using the struct extend syntax `..`.
+    pub fn arrow_bundle(&mut self, extend: impl FnOnce(NodeBundle) -> NodeBundle) -> &mut Self {
+        self.with_arrow();
+        if let Some(arrow) = self.arrow.as_mut() {
+            arrow.bundle = Some(extend(arrow.bundle.take().unwrap()));
+        }
+        self.arrow_color_from_root = false;
+        self
+    }
+
     /// Sets the tooltip content.
     /// The entity should be a valid UI node and will be added to the tooltip's tree when spawn() is called.
     pub fn with_content(&mut self, entity: Entity) -> &mut Self {
         self.content_entity = Some(entity);
+        self.text = None;
+        self
+    }
+
+    /// Sets the tooltip content to a text node.
+    /// The text can have multiple sections and can be changed later with the [`TooltipText`] component.
+    pub fn with_text(&mut self, text: Text) -> &mut Self {
+        self.text = Some(text);
+        self.content_entity = None;
         self
     }
 
     /// Spawns the entity and returns the EntityCommands for the root node.
     /// Using the builder again after calling this will panic.
     pub fn spawn(&mut self, commands: &'a mut Commands<'w, 's>) -> TooltipWidgetEntities {
+        let root_bundle = self.root.bundle.take().unwrap();
+        let root_color = root_bundle.color;
         let root = commands
-            .spawn_bundle(self.root.bundle.take().unwrap())
+            .spawn_bundle(root_bundle)
             .run_entity_commands(&self.root.commands_runners)
             .id();
 
-        if let Some(content) = self.content_entity {
+        // The arrow is added first so that it's drawn behind the content.
+        let arrow = self.arrow.as_mut().map(|arrow| {
+            let mut bundle = arrow.bundle.take().unwrap();
+            if self.arrow_color_from_root {
+                bundle.color = root_color;
+            }
+            commands
+                .spawn_bundle(bundle)
+                .run_entity_commands(&arrow.commands_runners)
+                .insert(TooltipArrowUiNode(root))
+                .id()
+        });
+
+        if let Some(arrow) = arrow {
+            commands.entity(root).add_child(arrow);
+        }
+
+        let content = match self.text.take() {
+            Some(text) => {
+                let text_node = commands
+                    .spawn_bundle(TextBundle {
+                        text: text.clone(),
+                        ..default()
+                    })
+                    .insert(TooltipTextUiNode(root))
+                    .id();
+                commands
+                    .entity(root)
+                    .insert(TooltipText(text))
+                    .insert(TooltipUiNodes {
+                        root,
+                        text: text_node,
+                    });
+                Some(text_node)
+            }
+            None => self.content_entity,
+        };
+
+        if let Some(content) = content {
             commands.entity(root).add_child(content);
         }
 
         TooltipWidgetEntities {
             root,
-            content: self.content_entity,
+            arrow,
+            content,
         }
     }
 }
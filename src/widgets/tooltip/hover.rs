@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use bevy_render::prelude::*;
+use bevy_text::prelude::*;
+use bevy_time::prelude::*;
+use bevy_ui::prelude::*;
+
+use super::*;
+
+/// Added to an interactive UI node to display a tooltip while it is hovered.
+///
+/// The node needs an [`Interaction`] component. The tooltip appears after the
+/// [`HoverTooltipSettings::show_delay`] and disappears after the [`HoverTooltipSettings::hide_delay`].
+/// While a tooltip is visible, hovering another node with a tooltip switches to it immediately.
+#[derive(Component, Clone, Debug)]
+pub enum HasTooltip {
+    /// Displays the text in a tooltip shared by every hovered node.
+    /// The shared tooltip is positioned next to the hovered node.
+    Text(Text),
+    /// Displays an existing tooltip entity, usually spawned with [`TooltipWidgetBuilder`].
+    /// Only the visibility of the tooltip is changed, so its position should be set by the user
+    /// and it should be spawned with [`Display::None`].
+    Entity(Entity),
+}
+
+impl HasTooltip {
+    /// Displays a single section of text in the shared tooltip.
+    pub fn text(value: impl Into<String>, style: TextStyle) -> Self {
+        Self::Text(Text::from_section(value, style))
+    }
+}
+
+/// Global settings for tooltips displayed by [`HasTooltip`].
+pub struct HoverTooltipSettings {
+    /// How long a node needs to be hovered before its tooltip is displayed.
+    pub show_delay: Duration,
+    /// How long the tooltip stays visible after the node stops being hovered.
+    pub hide_delay: Duration,
+    /// Alignment of the shared tooltip relative to the hovered node.
+    pub align: TooltipAlign,
+    /// Background color of the shared tooltip.
+    pub color: Color,
+    /// Whether the shared tooltip has an arrow pointing to the hovered node.
+    pub arrow: bool,
+}
+
+impl Default for HoverTooltipSettings {
+    fn default() -> Self {
+        Self {
+            show_delay: Duration::from_millis(500),
+            hide_delay: Duration::from_millis(100),
+            align: TooltipAlign::Top,
+            color: Color::rgb(0.15, 0.15, 0.15),
+            arrow: true,
+        }
+    }
+}
+
+/// Marker component for the tooltip shared by [`HasTooltip::Text`] nodes.
+///
+/// The shared tooltip has the colour of the [`HoverTooltipSettings`], unless a theme styles it, e.g.
+/// the `Tooltip` selector of the built-in theme. It can be styled separately from other tooltips
+/// with the `HoverTooltipNode` selector.
+#[derive(Component)]
+pub struct HoverTooltipNode;
+
+#[derive(Default)]
+pub(crate) struct HoverTooltipState {
+    /// The node that is currently hovered.
+    target: Option<Entity>,
+    /// The hovered node and the tooltip currently displayed for it.
+    shown: Option<(Entity, Entity)>,
+    /// Time spent hovering the target, or time since the shown node stopped being hovered.
+    elapsed: Duration,
+    /// The shared tooltip, spawned on first use.
+    shared: Option<Entity>,
+}
+
+pub(crate) fn hover_tooltip_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<HoverTooltipSettings>,
+    mut state: Local<HoverTooltipState>,
+    hover_q: Query<(
+        Entity,
+        &Interaction,
+        &HasTooltip,
+        ChangeTrackers<HasTooltip>,
+    )>,
+    mut tooltip_q: Query<(&mut Style, &mut TooltipPosition, &mut TooltipAlign), With<Tooltip>>,
+    mut text_q: Query<&mut TooltipText>,
+) {
+    let is_hovered = |interaction: &Interaction| !matches!(interaction, Interaction::None);
+    let hovered = state
+        .target
+        .filter(|target| {
+            hover_q
+                .get(*target)
+                .map_or(false, |(_, interaction, ..)| is_hovered(interaction))
+        })
+        .or_else(|| {
+            hover_q
+                .iter()
+                .find(|(_, interaction, ..)| is_hovered(interaction))
+                .map(|(entity, ..)| entity)
+        });
+
+    if hovered != state.target {
+        state.target = hovered;
+        state.elapsed = Duration::ZERO;
+    } else {
+        state.elapsed += time.delta();
+    }
+
+    let show = match (hovered, state.shown) {
+        // Switching between nodes doesn't wait for the show delay again.
+        (Some(target), Some((shown, _))) => {
+            target != shown
+                || hover_q
+                    .get(target)
+                    .map_or(false, |(.., tracker)| tracker.is_changed())
+        }
+        (Some(_), None) => state.elapsed >= settings.show_delay,
+        (None, Some((shown, _))) => {
+            if state.elapsed >= settings.hide_delay || !hover_q.contains(shown) {
+                hide_tooltip(&mut state, &mut tooltip_q);
+            }
+            false
+        }
+        (None, None) => false,
+    };
+
+    if let Some((target, has_tooltip)) = hovered.filter(|_| show).and_then(|target| {
+        hover_q
+            .get(target)
+            .ok()
+            .map(|(_, _, has_tooltip, _)| (target, has_tooltip))
+    }) {
+        hide_tooltip(&mut state, &mut tooltip_q);
+
+        let tooltip = match has_tooltip {
+            HasTooltip::Text(text) => match state.shared {
+                Some(root) => {
+                    if let Ok(mut tooltip_text) = text_q.get_mut(root) {
+                        tooltip_text.0 = text.clone();
+                    }
+                    if let Ok((_, mut position, mut align)) = tooltip_q.get_mut(root) {
+                        *position = TooltipPosition::Node(target);
+                        *align = settings.align;
+                    }
+                    root
+                }
+                None => {
+                    let tooltip = {
+                        let mut builder = TooltipWidgetBuilder::new();
+                        builder
+                            .root_bundle(|bundle| TooltipBundle {
+                                position: TooltipPosition::Node(target),
+                                align: settings.align,
+                                color: settings.color.into(),
+                                ..bundle
+                            })
+                            .with_text(text.clone());
+                        if settings.arrow {
+                            builder.with_arrow();
+                        }
+                        builder.spawn(&mut commands)
+                    };
+                    commands.entity(tooltip.root).insert(HoverTooltipNode);
+                    state.shared = Some(tooltip.root);
+                    tooltip.root
+                }
+            },
+            HasTooltip::Entity(tooltip) => *tooltip,
+        };
+
+        if let Ok((mut style, ..)) = tooltip_q.get_mut(tooltip) {
+            style.display = Display::Flex;
+        }
+        state.shown = Some((target, tooltip));
+    }
+}
+
+fn hide_tooltip(
+    state: &mut HoverTooltipState,
+    tooltip_q: &mut Query<(&mut Style, &mut TooltipPosition, &mut TooltipAlign), With<Tooltip>>,
+) {
+    if let Some((_, tooltip)) = state.shown.take() {
+        if let Ok((mut style, ..)) = tooltip_q.get_mut(tooltip) {
+            style.display = Display::None;
+        }
+    }
+}
@@ -1,27 +1,60 @@
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
 use bevy_math::prelude::*;
 use bevy_render::prelude::*;
 use bevy_text::prelude::*;
-use bevy_transform::prelude::*;
-use bevy_ui::{prelude::*, FocusPolicy};
+use bevy_transform::{prelude::*, TransformSystem};
+use bevy_ui::{prelude::*, FocusPolicy, UiSystem};
 use bevy_utils::prelude::*;
 use bevy_window::prelude::*;
 
+use crate::theming::ThemeMarkerAppExt;
+
 mod builder;
+mod hover;
+
 pub use builder::*;
+pub use hover::*;
 
 pub struct TooltipPlugin;
 
 impl Plugin for TooltipPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_to_stage(CoreStage::PreUpdate, position_update_system)
-            .add_system_to_stage(CoreStage::PreUpdate, position_update_cursor_system)
-            .add_system_to_stage(CoreStage::PreUpdate, position_update_node_system)
+        app.init_resource::<HoverTooltipSettings>()
+            .register_theme_marker::<Tooltip>("Tooltip")
+            .register_theme_marker::<TooltipTextUiNode>("TooltipTextUiNode")
+            .register_theme_marker::<TooltipArrowUiNode>("TooltipArrowUiNode")
+            .register_theme_marker::<HoverTooltipNode>("HoverTooltipNode")
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                position_update_rect_system.before(UiSystem::Flex),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                position_update_system
+                    .label(TooltipSystem::Position)
+                    .after(UiSystem::Flex)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                arrow_update_system
+                    .after(TooltipSystem::Position)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system(hover_tooltip_system)
             .add_system(update_text);
     }
 }
 
+/// Labels for the tooltip systems that run after the UI layout.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum TooltipSystem {
+    /// Positions tooltips relative to their anchor.
+    Position,
+}
+
 /// Marker component present on all tooltips.
 #[derive(Component, Default, Debug)]
 pub struct Tooltip;
@@ -35,8 +68,13 @@ pub enum TooltipPosition {
     FollowCursor,
     /// Tooltip is positioned relative to a node.
     Node(Entity),
-    /// Uses absolute positioning on the screen
+    /// Uses absolute positioning on the screen: the tooltip's top-left corner is placed at the
+    /// given position, measured from the top-left corner of the window like `style.position`.
+    /// Alignment and collisions are ignored.
     Absolute(Vec2),
+    /// Tooltip is aligned around a point on the screen, measured from the top-left corner of the
+    /// window, like it is aligned around a node.
+    AbsoluteAnchor(Vec2),
     /// Raw rect. This is the same as using Manual and then setting style.rect
     Rect(UiRect<Val>),
     /// Disables automatic positioning of the tooltip node.
@@ -50,17 +88,20 @@ impl Default for TooltipPosition {
 }
 
 /// Describes the alignment of the tooltip relative to its position.
-/// This will be ignored when using a Rect position.
-#[derive(Component, Debug)]
+/// This will be ignored when using a Rect or Absolute position.
+///
+/// Corner alignments place the tooltip diagonally from the anchor, e.g. with `TopLeft` the
+/// tooltip's bottom-right corner touches the anchor's top-left corner.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TooltipAlign {
     Bottom,
-    //BottomLeft,
-    //BottomRight,
+    BottomLeft,
+    BottomRight,
     Left,
     Right,
     Top,
-    //TopLeft,
-    //TopRight,
+    TopLeft,
+    TopRight,
 }
 
 impl Default for TooltipAlign {
@@ -69,12 +110,94 @@ impl Default for TooltipAlign {
     }
 }
 
+impl TooltipAlign {
+    /// Returns the direction in which the tooltip is placed relative to its anchor.
+    /// Each axis is either -1.0, 0.0 or 1.0, with the y axis pointing up.
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            TooltipAlign::Bottom => Vec2::new(0.0, -1.0),
+            TooltipAlign::BottomLeft => Vec2::new(-1.0, -1.0),
+            TooltipAlign::BottomRight => Vec2::new(1.0, -1.0),
+            TooltipAlign::Left => Vec2::new(-1.0, 0.0),
+            TooltipAlign::Right => Vec2::new(1.0, 0.0),
+            TooltipAlign::Top => Vec2::new(0.0, 1.0),
+            TooltipAlign::TopLeft => Vec2::new(-1.0, 1.0),
+            TooltipAlign::TopRight => Vec2::new(1.0, 1.0),
+        }
+    }
+
+    /// Returns the alignment on the other side of the anchor horizontally (e.g. `Left` becomes `Right`).
+    pub fn flip_x(&self) -> Self {
+        match self {
+            TooltipAlign::BottomLeft => TooltipAlign::BottomRight,
+            TooltipAlign::BottomRight => TooltipAlign::BottomLeft,
+            TooltipAlign::Left => TooltipAlign::Right,
+            TooltipAlign::Right => TooltipAlign::Left,
+            TooltipAlign::TopLeft => TooltipAlign::TopRight,
+            TooltipAlign::TopRight => TooltipAlign::TopLeft,
+            align => *align,
+        }
+    }
+
+    /// Returns the alignment on the other side of the anchor vertically (e.g. `Top` becomes `Bottom`).
+    pub fn flip_y(&self) -> Self {
+        match self {
+            TooltipAlign::Bottom => TooltipAlign::Top,
+            TooltipAlign::BottomLeft => TooltipAlign::TopLeft,
+            TooltipAlign::BottomRight => TooltipAlign::TopRight,
+            TooltipAlign::Top => TooltipAlign::Bottom,
+            TooltipAlign::TopLeft => TooltipAlign::BottomLeft,
+            TooltipAlign::TopRight => TooltipAlign::BottomRight,
+            align => *align,
+        }
+    }
+}
+
+/// Describes how the tooltip reacts when it would be placed partially outside of the window.
+/// This will be ignored when using a Rect, Absolute or Manual position.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TooltipCollision {
+    /// The tooltip is allowed to go outside of the window.
+    None,
+    /// The tooltip is moved back inside the window, even if it overlaps its anchor.
+    Shift,
+    /// The tooltip is moved to the opposite side of its anchor when there is not enough space
+    /// for it, then shifted inside the window if it still doesn't fit.
+    Flip,
+}
+
+impl Default for TooltipCollision {
+    fn default() -> Self {
+        Self::Flip
+    }
+}
+
+/// The alignment that was actually used to position the tooltip.
+///
+/// This is the same as [`TooltipAlign`] unless the tooltip was flipped to avoid a collision
+/// with the window edges.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TooltipResolvedAlign(pub TooltipAlign);
+
 /// The tooltip's text.
 ///
 /// When present, the text will automatically be copied to the tooltip's child text node.
+/// The text can have multiple sections to display rich text with different styles.
 #[derive(Component)]
 pub struct TooltipText(pub Text);
 
+impl TooltipText {
+    /// Creates a tooltip text with a single section.
+    pub fn from_section(value: impl Into<String>, style: TextStyle) -> Self {
+        Self(Text::from_section(value, style))
+    }
+
+    /// Creates a tooltip text with multiple sections, each with their own style.
+    pub fn from_sections(sections: impl IntoIterator<Item = TextSection>) -> Self {
+        Self(Text::from_sections(sections))
+    }
+}
+
 #[derive(Component)]
 pub struct TooltipUiNodes {
     /// Root node created via NodeBundle
@@ -88,20 +211,27 @@ pub struct TooltipUiNodes {
 #[derive(Component)]
 pub struct TooltipTextUiNode(pub Entity);
 
-fn position_update_system(
+/// Marker component used to identify the arrow node.
+/// Also contains a reference to the tooltip's root entity.
+#[derive(Component)]
+pub struct TooltipArrowUiNode(pub Entity);
+
+/// Applies positions that don't depend on the layout, so they are used by the layout of the same frame.
+fn position_update_rect_system(
     mut tooltip_q: Query<
-        (&TooltipPosition, &TooltipAlign, &mut Style),
-        Or<(
-            Changed<TooltipUiNodes>,
-            Changed<TooltipPosition>,
-            Changed<TooltipAlign>,
-        )>,
+        (
+            &TooltipPosition,
+            &TooltipAlign,
+            &mut Style,
+            Option<&mut TooltipResolvedAlign>,
+        ),
+        (
+            With<Tooltip>,
+            Or<(Changed<TooltipPosition>, Changed<TooltipAlign>)>,
+        ),
     >,
-    //node_query: Query<(&Node, &GlobalTransform)>,
 ) {
-    for (position, _, mut style) in tooltip_q.iter_mut() {
-        // TODO: handle TooltipAlign (we essentially change the top, left, right, bottom values accordingly)
-        // TODO: handle collision with screen edges. we could probably use an invisible global node to get edges instead of Res<Window>.
+    for (position, align, mut style, resolved_align) in tooltip_q.iter_mut() {
         match position {
             TooltipPosition::Absolute(pos) => {
                 style.position = UiRect {
@@ -109,105 +239,228 @@ fn position_update_system(
                     left: Val::Px(pos.x),
                     ..default()
                 };
+                update_resolved_align(resolved_align, *align);
             }
             TooltipPosition::Rect(rect) => {
                 style.position = *rect;
+                update_resolved_align(resolved_align, *align);
             }
-            _ => (),
-        }
-    }
-}
-
-fn position_update_cursor_system(
-    mut tooltip_q: Query<(&TooltipPosition, &TooltipAlign, &Node, &mut Style), With<Tooltip>>,
-    windows: Res<Windows>,
-) {
-    for (position, align, node, mut style) in tooltip_q.iter_mut() {
-        if let TooltipPosition::FollowCursor = position {
-            let window = windows.get_primary().unwrap();
-            if let Some(pos) = window.cursor_position() {
-                style.position = calculate_tooltip_rect(align, &node.size, &pos, 5.0);
+            TooltipPosition::Manual => {
+                update_resolved_align(resolved_align, *align);
             }
+            _ => (),
         }
     }
 }
 
-fn position_update_node_system(
+/// Positions tooltips relative to their anchor.
+///
+/// This runs after the UI layout has been calculated, so that the size of the tooltip and the position
+/// of its anchor are up to date even on the first frame a tooltip is displayed. The tooltip's transform
+/// is moved directly so that the new position is used before the next layout calculation.
+/// Tooltips are expected to be root UI nodes.
+fn position_update_system(
     mut tooltip_q: Query<
         (
             &TooltipPosition,
             &TooltipAlign,
-            &GlobalTransform,
+            Option<&TooltipCollision>,
             &Node,
             &mut Style,
+            &mut Transform,
+            Option<&mut TooltipResolvedAlign>,
         ),
         With<Tooltip>,
     >,
-    node_query: Query<(&GlobalTransform, &Node)>,
+    node_q: Query<(&Node, &Transform, Option<&Parent>), Without<Tooltip>>,
+    windows: Res<Windows>,
 ) {
-    for (position, align, _transform, node, mut style) in tooltip_q.iter_mut() {
-        if let TooltipPosition::Node(position_entity) = position {
-            if let Ok((other_transform, other_node)) = node_query.get(*position_entity) {
-                if node.size == Vec2::splat(0.0) {
-                    // NOTE: node size is 0.0 for one frame when we update display, because it was not yet calculated.
-                    //  we will have to find a solution at some point, but for now this is a good-enough hack around it.
-                    return;
-                }
-
-                let point = calculate_node_point(
-                    align,
-                    &other_node.size,
-                    &other_transform.translation().truncate(),
-                );
-                let rect = calculate_tooltip_rect(align, &node.size, &point, 2.0);
-                if style.position != rect {
-                    style.position = rect;
-                }
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+
+    for (position, align, collision, node, mut style, mut transform, resolved_align) in
+        tooltip_q.iter_mut()
+    {
+        if node.size == Vec2::ZERO {
+            // The tooltip is hidden or not measured yet, it will be positioned once it has a size.
+            continue;
+        }
+
+        let (anchor, offset) = match position {
+            TooltipPosition::FollowCursor => match window.cursor_position() {
+                Some(cursor_position) => ((cursor_position, Vec2::ZERO), 5.0),
+                None => continue,
+            },
+            TooltipPosition::Node(entity) => match node_q.get(*entity) {
+                Ok((anchor_node, ..)) => (
+                    (
+                        calculate_layout_position(*entity, &node_q),
+                        anchor_node.size,
+                    ),
+                    2.0,
+                ),
+                Err(_) => continue,
+            },
+            // Absolute positions use the same top-left origin as `Style::position`.
+            TooltipPosition::AbsoluteAnchor(pos) => {
+                ((Vec2::new(pos.x, window_size.y - pos.y), Vec2::ZERO), 0.0)
             }
+            TooltipPosition::Absolute(_) | TooltipPosition::Rect(_) | TooltipPosition::Manual => {
+                continue
+            }
+        };
+
+        let (resolved, min) = calculate_tooltip_placement(
+            align,
+            collision.copied().unwrap_or_default(),
+            anchor,
+            node.size,
+            offset,
+            window_size,
+        );
+
+        let rect = UiRect {
+            left: Val::Px(min.x),
+            bottom: Val::Px(min.y),
+            ..default()
+        };
+        if style.position != rect {
+            style.position = rect;
         }
+
+        let center = min + node.size / 2.0;
+        if transform.translation.truncate() != center {
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+        }
+
+        update_resolved_align(resolved_align, resolved);
     }
 }
 
-fn calculate_node_point(
-    align: &TooltipAlign,
-    size: &Vec2,
-    position: &Vec2,
+/// Calculates the center of a UI node in window coordinates from the transforms set by the layout.
+///
+/// This doesn't use [`GlobalTransform`] since it is only propagated after the tooltips are positioned.
+fn calculate_layout_position(
+    entity: Entity,
+    node_q: &Query<(&Node, &Transform, Option<&Parent>), Without<Tooltip>>,
 ) -> Vec2 {
-    match align {
-        TooltipAlign::Bottom => Vec2::new(position.x, position.y - (size.y / 2.0)),
-        TooltipAlign::Left => Vec2::new(position.x - (size.x / 2.0), position.y),
-        TooltipAlign::Right => Vec2::new(position.x + (size.x / 2.0), position.y),
-        TooltipAlign::Top => Vec2::new(position.x, position.y + (size.y / 2.0)),
+    let mut position = Vec2::ZERO;
+    let mut current = Some(entity);
+    while let Some((_, transform, parent)) = current.and_then(|entity| node_q.get(entity).ok()) {
+        position += transform.translation.truncate();
+        current = parent.map(|parent| parent.get());
     }
+    position
 }
 
-fn calculate_tooltip_rect(
+/// Calculates the bottom-left corner of a tooltip placed around an anchor.
+///
+/// The anchor is described by its center and its size, which is zero for points.
+fn calculate_tooltip_min(
     align: &TooltipAlign,
-    tooltip_size: &Vec2,
-    point: &Vec2,
+    (anchor_center, anchor_size): (Vec2, Vec2),
+    tooltip_size: Vec2,
     offset: f32,
-) -> UiRect<Val> {
-    match align {
-        TooltipAlign::Bottom => UiRect {
-            bottom: Val::Px(point.y - tooltip_size.y - offset),
-            left: Val::Px(point.x - (tooltip_size.x / 2.0)),
-            ..default()
-        },
-        TooltipAlign::Left => UiRect {
-            left: Val::Px(point.x - tooltip_size.x - offset),
-            bottom: Val::Px(point.y - (tooltip_size.y / 2.0)),
-            ..default()
-        },
-        TooltipAlign::Right => UiRect {
-            left: Val::Px(point.x + offset),
-            bottom: Val::Px(point.y - (tooltip_size.y / 2.0)),
-            ..default()
-        },
-        TooltipAlign::Top => UiRect {
-            bottom: Val::Px(point.y + offset),
-            left: Val::Px(point.x - (tooltip_size.x / 2.0)),
-            ..default()
-        },
+) -> Vec2 {
+    let direction = align.direction();
+    let point = anchor_center + direction * (anchor_size / 2.0);
+    let center = point + direction * (tooltip_size / 2.0 + offset);
+    center - tooltip_size / 2.0
+}
+
+/// Returns how far a tooltip placed at `min` goes outside of the window on each axis.
+fn calculate_overflow(min: Vec2, tooltip_size: Vec2, window_size: Vec2) -> Vec2 {
+    let before = (-min).max(Vec2::ZERO);
+    let after = (min + tooltip_size - window_size).max(Vec2::ZERO);
+    before + after
+}
+
+/// Calculates the tooltip alignment and bottom-left corner after handling collisions with the
+/// window edges.
+fn calculate_tooltip_placement(
+    align: &TooltipAlign,
+    collision: TooltipCollision,
+    anchor: (Vec2, Vec2),
+    tooltip_size: Vec2,
+    offset: f32,
+    window_size: Vec2,
+) -> (TooltipAlign, Vec2) {
+    let mut align = *align;
+    let mut min = calculate_tooltip_min(&align, anchor, tooltip_size, offset);
+
+    if collision == TooltipCollision::Flip {
+        // Flipping one axis doesn't move the tooltip on the other, so the overflow of each axis is
+        // only checked once, but the flips are applied to the alignment updated by earlier flips.
+        let overflow = calculate_overflow(min, tooltip_size, window_size);
+        for horizontal in [true, false] {
+            let (overflows, flipped) = if horizontal {
+                (overflow.x > 0.0, align.flip_x())
+            } else {
+                (overflow.y > 0.0, align.flip_y())
+            };
+            if !overflows || flipped == align {
+                continue;
+            }
+            let flipped_min = calculate_tooltip_min(&flipped, anchor, tooltip_size, offset);
+            let flipped_overflow = calculate_overflow(flipped_min, tooltip_size, window_size);
+            let current_overflow = calculate_overflow(min, tooltip_size, window_size);
+            if flipped_overflow.x + flipped_overflow.y < current_overflow.x + current_overflow.y {
+                align = flipped;
+                min = flipped_min;
+            }
+        }
+    }
+
+    if collision != TooltipCollision::None {
+        // Shifting favors the bottom-left corner when the tooltip is bigger than the window.
+        min = min.min(window_size - tooltip_size).max(Vec2::ZERO);
+    }
+
+    (align, min)
+}
+
+fn update_resolved_align(resolved_align: Option<Mut<TooltipResolvedAlign>>, align: TooltipAlign) {
+    if let Some(mut resolved_align) = resolved_align {
+        if resolved_align.0 != align {
+            resolved_align.0 = align;
+        }
+    }
+}
+
+/// Places arrows on the side of the tooltip facing its anchor.
+///
+/// Like [`position_update_system`], this moves the arrow's transform directly after the layout is calculated.
+fn arrow_update_system(
+    tooltip_q: Query<(&TooltipResolvedAlign, &Node), With<Tooltip>>,
+    mut arrow_q: Query<(&TooltipArrowUiNode, &Node, &mut Style, &mut Transform)>,
+) {
+    for (arrow, arrow_node, mut style, mut transform) in arrow_q.iter_mut() {
+        if let Ok((align, tooltip_node)) = tooltip_q.get(arrow.0) {
+            // The arrow points in the opposite direction of the tooltip placement.
+            let offset = -align.0.direction() * (tooltip_node.size / 2.0);
+            let min = tooltip_node.size / 2.0 + offset - arrow_node.size / 2.0;
+            let rect = UiRect {
+                left: Val::Px(min.x),
+                bottom: Val::Px(min.y),
+                ..default()
+            };
+            if style.position != rect {
+                style.position = rect;
+            }
+
+            let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_4);
+            if transform.rotation != rotation {
+                transform.rotation = rotation;
+            }
+            if transform.translation.truncate() != offset {
+                transform.translation.x = offset.x;
+                transform.translation.y = offset.y;
+            }
+        }
     }
 }
 
@@ -229,6 +482,8 @@ pub struct TooltipBundle {
     pub tooltip: Tooltip,
     pub position: TooltipPosition,
     pub align: TooltipAlign,
+    pub collision: TooltipCollision,
+    pub resolved_align: TooltipResolvedAlign,
     /// Describes the size of the node
     pub node: Node,
     /// Describes the style including flexbox settings
@@ -255,6 +510,8 @@ impl Default for TooltipBundle {
             tooltip: default(),
             position: TooltipPosition::FollowCursor,
             align: TooltipAlign::Right,
+            collision: default(),
+            resolved_align: TooltipResolvedAlign(TooltipAlign::Right),
             node: default(),
             style: Self::default_style(),
             color: default(),
@@ -278,3 +535,115 @@ impl TooltipBundle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Vec2 = Vec2::new(200.0, 120.0);
+
+    fn place(
+        align: TooltipAlign,
+        collision: TooltipCollision,
+        anchor_center: Vec2,
+        tooltip_size: Vec2,
+    ) -> (TooltipAlign, Vec2) {
+        let anchor = (anchor_center, Vec2::new(20.0, 20.0));
+        calculate_tooltip_placement(&align, collision, anchor, tooltip_size, 0.0, WINDOW)
+    }
+
+    #[test]
+    fn fitting_tooltips_keep_their_alignment() {
+        let placement = place(
+            TooltipAlign::Top,
+            TooltipCollision::Flip,
+            Vec2::new(100.0, 50.0),
+            Vec2::new(40.0, 10.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Top, Vec2::new(80.0, 60.0)));
+    }
+
+    #[test]
+    fn overflowing_tooltips_flip_to_the_other_side() {
+        let placement = place(
+            TooltipAlign::Top,
+            TooltipCollision::Flip,
+            Vec2::new(100.0, 105.0),
+            Vec2::new(40.0, 10.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Bottom, Vec2::new(80.0, 85.0)));
+    }
+
+    #[test]
+    fn corners_flip_on_both_axes() {
+        let placement = place(
+            TooltipAlign::TopLeft,
+            TooltipCollision::Flip,
+            Vec2::new(10.0, 105.0),
+            Vec2::new(40.0, 10.0),
+        );
+        assert_eq!(
+            placement,
+            (TooltipAlign::BottomRight, Vec2::new(20.0, 85.0))
+        );
+    }
+
+    #[test]
+    fn corners_flip_only_the_overflowing_axis() {
+        let placement = place(
+            TooltipAlign::BottomRight,
+            TooltipCollision::Flip,
+            Vec2::new(190.0, 60.0),
+            Vec2::new(40.0, 10.0),
+        );
+        assert_eq!(
+            placement,
+            (TooltipAlign::BottomLeft, Vec2::new(140.0, 40.0))
+        );
+    }
+
+    #[test]
+    fn flips_that_dont_help_are_shifted_instead() {
+        // The tooltip overflows by the same amount on both sides of the anchor.
+        let placement = place(
+            TooltipAlign::Top,
+            TooltipCollision::Flip,
+            Vec2::new(100.0, 60.0),
+            Vec2::new(20.0, 70.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Top, Vec2::new(90.0, 50.0)));
+    }
+
+    #[test]
+    fn shift_moves_the_tooltip_inside_the_window() {
+        let placement = place(
+            TooltipAlign::Right,
+            TooltipCollision::Shift,
+            Vec2::new(190.0, 60.0),
+            Vec2::new(30.0, 10.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Right, Vec2::new(170.0, 55.0)));
+    }
+
+    #[test]
+    fn tooltips_bigger_than_the_window_favor_the_bottom_left_corner() {
+        let placement = place(
+            TooltipAlign::Top,
+            TooltipCollision::Shift,
+            Vec2::new(100.0, 60.0),
+            Vec2::new(300.0, 10.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Top, Vec2::new(0.0, 70.0)));
+    }
+
+    #[test]
+    fn no_collision_keeps_the_tooltip_outside() {
+        let placement = place(
+            TooltipAlign::Left,
+            TooltipCollision::None,
+            Vec2::new(10.0, 60.0),
+            Vec2::new(30.0, 10.0),
+        );
+        assert_eq!(placement, (TooltipAlign::Left, Vec2::new(-30.0, 55.0)));
+    }
+}